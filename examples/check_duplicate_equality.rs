@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::Hasher;
-use std::path::PathBuf;
+use std::path::Path;
 use std::sync::Arc;
 use thrumzip::get_zips::get_zips;
 use thrumzip::path_inside_zip::PathInsideZip;
@@ -55,7 +55,7 @@ async fn main() -> Result<()> {
             // this is super inefficient
             if let Some(entry) = archive.entries().find(|e| {
                 e.sanitized_name()
-                    .map(|n| PathBuf::from(n) == **name)
+                    .map(|n| Path::new(n) == &**name)
                     .unwrap_or(false)
             }) {
                 let data = entry.bytes().await?;
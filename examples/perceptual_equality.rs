@@ -9,6 +9,7 @@ use img_hash::ImageHash;
 use positioned_io::RandomAccessFile;
 use rc_zip_tokio::ReadZip;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use thrumzip::get_zips::get_zips;
@@ -171,7 +172,7 @@ async fn main() -> Result<()> {
                 // Find the specific entry again
                 let entry_opt = archive.entries().find(|e| {
                     e.sanitized_name()
-                        .is_some_and(|n| PathBuf::from(n) == **task_entry_path_buf)
+                        .is_some_and(|n| Path::new(n) == &**task_entry_path_buf)
                 });
 
                 if let Some(entry) = entry_opt {
@@ -69,29 +69,27 @@ async fn main() -> Result<()> {
             let arch = f.read_zip().await?;
             let mut list = Vec::new();
             for ent in arch.entries() {
-                if let Some(name) = ent.sanitized_name() {
-                    if !name.ends_with('/') {
-                        let ext = Path::new(&name)
-                            .extension()
-                            .and_then(|e| e.to_str())
-                            .map(|e| e.to_ascii_lowercase());
-                        if let Some(ext) = ext {
-                            if !image_exts.contains(&ext.as_str()) {
-                                continue;
-                            }
-                        } else {
+                if let Some(name) = ent.sanitized_name().filter(|name| !name.ends_with('/')) {
+                    let ext = Path::new(&name)
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .map(|e| e.to_ascii_lowercase());
+                    if let Some(ext) = ext {
+                        if !image_exts.contains(&ext.as_str()) {
                             continue;
                         }
-                        let key = PathInsideZip::new(Arc::new(PathBuf::from(name)));
-                        list.push((
-                            key,
-                            RawInfo {
-                                zip: zip_file_path.clone(),
-                                crc: ent.crc32,
-                                size: ent.uncompressed_size,
-                            },
-                        ));
+                    } else {
+                        continue;
                     }
+                    let key = PathInsideZip::new(Arc::new(PathBuf::from(name)));
+                    list.push((
+                        key,
+                        RawInfo {
+                            zip: zip_file_path.clone(),
+                            crc: ent.crc32,
+                            size: ent.uncompressed_size,
+                        },
+                    ));
                 }
             }
             Ok::<_, eyre::Report>(list)
@@ -67,10 +67,11 @@ async fn main() -> Result<()> {
                 }
                 let key = name.to_owned();
                 let size = entry.uncompressed_size;
-                if let Some((prev_mod, _)) = local.get(&key) {
-                    if *prev_mod >= modified {
-                        continue;
-                    }
+                if local
+                    .get(&key)
+                    .is_some_and(|(prev_mod, _)| *prev_mod >= modified)
+                {
+                    continue;
                 }
                 local.insert(key, (modified, size));
             }
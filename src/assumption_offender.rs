@@ -0,0 +1,9 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// An entry name that breaks one of the assumptions in `Assumptions.md`.
+#[derive(Serialize, Debug, Clone)]
+pub struct AssumptionOffender {
+    pub path_inside_zip: PathBuf,
+    pub detail: String,
+}
@@ -0,0 +1,26 @@
+use crate::assumption_offender::AssumptionOffender;
+use serde::Serialize;
+
+/// Whether the source zips hold up one of the assumptions in `Assumptions.md`.
+#[derive(Serialize, Debug, Clone)]
+pub struct AssumptionResult {
+    pub assumption: String,
+    pub passed: bool,
+    pub offenders: Vec<AssumptionOffender>,
+    /// Entries that could not be checked against the assumption, which do not fail it
+    pub unverifiable: Vec<AssumptionOffender>,
+}
+impl AssumptionResult {
+    pub fn new(
+        assumption: &str,
+        offenders: Vec<AssumptionOffender>,
+        unverifiable: Vec<AssumptionOffender>,
+    ) -> Self {
+        Self {
+            assumption: assumption.to_string(),
+            passed: offenders.is_empty(),
+            offenders,
+            unverifiable,
+        }
+    }
+}
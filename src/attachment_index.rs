@@ -0,0 +1,31 @@
+use crate::existing_file::ExistingFile;
+use crate::meta::messages::media::Media;
+use crate::path_inside_zip::PathInsideZip;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Destination files by their path inside the zip, for finding the file an attachment uri points at.
+pub struct AttachmentIndex {
+    files_by_name: HashMap<PathInsideZip, Vec<PathBuf>>,
+}
+impl AttachmentIndex {
+    pub fn new(existing_files: &[ExistingFile]) -> Self {
+        let files_by_name = existing_files
+            .iter()
+            // Canonical files first, so they are preferred over disambiguated copies
+            .sorted_by_key(|file| file.is_ambiguous())
+            .map(|file| (file.path_inside_zip().to_owned(), file.disk_path().clone()))
+            .into_group_map();
+        Self { files_by_name }
+    }
+
+    /// The destination file an attachment uri points at, preferring the canonical copy.
+    pub fn resolve(&self, media: &Media) -> Option<&Path> {
+        self.files_by_name
+            .get(&PathInsideZip::from(PathBuf::from(&media.uri)))
+            .and_then(|paths| paths.first())
+            .map(PathBuf::as_path)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::path_to_zip::PathToZip;
+use crate::perceptual::SYNC_SIMILARITY_THRESHOLD;
+use crate::perceptual::hash_images;
+use crate::perceptual::max_distance;
+use crate::perceptual::sync_compares_perceptually;
+use crate::zip_entry::ZipEntry;
+use chrono::NaiveDate;
+use eyre::OptionExt;
+use img_hash::HasherConfig;
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Picks the variant of an entry name that sync writes without disambiguation, following the same rules:
+/// identical CRC32s are interchangeable, and perceptually equivalent images resolve to the smallest one.
+///
+/// When sync would keep several variants, the one from the newest export is returned along with `true`.
+pub async fn canonical_variant(
+    variants: Vec<ZipEntry>,
+    export_dates: &HashMap<PathToZip, NaiveDate>,
+    hasher_config: &HasherConfig,
+) -> eyre::Result<(ZipEntry, bool)> {
+    let variants = variants
+        .into_iter()
+        .sorted_by_key(|variant| Reverse(export_dates.get(&variant.path_to_zip).copied()))
+        .collect_vec();
+    let newest = variants.first().cloned().ok_or_eyre("No variants given")?;
+    if variants
+        .iter()
+        .all(|variant| variant.entry.crc32 == newest.entry.crc32)
+    {
+        return Ok((newest, false));
+    }
+    let hashes = if sync_compares_perceptually(&newest.path_inside_zip) {
+        hash_images(&variants, hasher_config).await?
+    } else {
+        None
+    };
+    if hashes.is_some_and(|hashes| max_distance(&hashes) <= SYNC_SIMILARITY_THRESHOLD) {
+        let smallest = variants
+            .into_iter()
+            .min_by_key(|variant| variant.entry.uncompressed_size)
+            .unwrap_or(newest);
+        return Ok((smallest, false));
+    }
+    Ok((newest, true))
+}
@@ -0,0 +1,17 @@
+use crate::path_inside_zip::PathInsideZip;
+use itertools::Itertools;
+
+/// Groups distinct paths that would land on the same file on a case-insensitive destination such as NTFS, exFAT or APFS.
+pub fn find_case_collisions<'a>(
+    paths: impl IntoIterator<Item = &'a PathInsideZip>,
+) -> Vec<Vec<PathInsideZip>> {
+    paths
+        .into_iter()
+        .unique()
+        .into_group_map_by(|path| path.case_folded())
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|group| group.into_iter().cloned().sorted().collect_vec())
+        .sorted()
+        .collect_vec()
+}
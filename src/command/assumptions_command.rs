@@ -0,0 +1,315 @@
+use crate::assumption_offender::AssumptionOffender;
+use crate::assumption_result::AssumptionResult;
+use crate::command::GlobalArgs;
+use crate::export::Export;
+use crate::export::group_zips_by_export;
+use crate::get_zips;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::path_inside_zip::PathInsideZip;
+use crate::perceptual::hash_images;
+use crate::perceptual::image_hasher_config;
+use crate::perceptual::is_image;
+use crate::perceptual::max_distance;
+use crate::progress::worker::track_progress;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::size_of_thing::KnownSize;
+use crate::skipped_entry::SkippedEntry;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use crate::zip_entry::ZipEntry;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+const CONTAINMENT: &str = "Newer archives SHOULD contain all entries present in older archives";
+const SIMILARITY: &str =
+    "Archive entries with duplicate keys MUST have 99% similar perceptual content hashes";
+
+#[derive(Args)]
+pub struct AssumptionsCommand {
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+    /// Maximum number of offending paths to print per assumption in the table format
+    #[clap(long, default_value_t = 50)]
+    pub limit: usize,
+    /// Exit with an error when an assumption fails
+    #[clap(long)]
+    pub strict: bool,
+}
+
+impl AssumptionsCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+
+        info!("Gathering zip files from sources...");
+        let (zips, zips_size) = get_zips::get_zips(&app_profile.sources).await?;
+        info!(
+            "Found {} zip files in the source paths ({})",
+            zips.len(),
+            zips_size.human_size()
+        );
+        let exports = group_zips_by_export(&zips).await?;
+        info!("Found {} exports", exports.len());
+
+        info!("Reading entries from zips...");
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        info!(
+            "Found {} entries ({}) in the source zips",
+            entries.len(),
+            entries.human_size()
+        );
+
+        let results = vec![
+            check_containment(&exports, &entries, &skipped),
+            check_similarity(entries, app_profile.similarity).await?,
+        ];
+        print_results(&results, self.format, self.limit)?;
+        report_skipped(&skipped);
+
+        if self.strict && results.iter().any(|result| !result.passed) {
+            bail!("Some assumptions do not hold for the source zips");
+        }
+        Ok(())
+    }
+}
+
+/// Reports names found in one export that are absent from the next one.
+/// Zips with skipped entries are left out, an export containing one is not compared against the one before it.
+fn check_containment(
+    exports: &[Export],
+    entries: &[ZipEntry],
+    skipped: &[SkippedEntry],
+) -> AssumptionResult {
+    let skipped_zips = skipped
+        .iter()
+        .map(|skipped| &skipped.path_to_zip)
+        .collect::<HashSet<_>>();
+    let export_by_zip = exports
+        .iter()
+        .flat_map(|export| {
+            export
+                .zips
+                .iter()
+                .map(move |zip| (zip.clone(), export.date))
+        })
+        .collect::<HashMap<_, _>>();
+    let names_by_export = entries
+        .iter()
+        .into_group_map_by(|entry| export_by_zip[&entry.path_to_zip])
+        .into_iter()
+        .map(|(date, entries)| {
+            (
+                date,
+                entries
+                    .into_iter()
+                    .map(|entry| entry.path_inside_zip.clone())
+                    .collect::<HashSet<PathInsideZip>>(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    // An export with skipped entries would report them as missing, so each export is compared with the next complete one
+    let (complete, incomplete): (Vec<&Export>, Vec<&Export>) = exports
+        .iter()
+        .partition(|export| !export.zips.iter().any(|zip| skipped_zips.contains(zip)));
+    for export in incomplete {
+        info!(
+            "Not checking containment in the {} export, it has skipped entries",
+            export.date
+        );
+    }
+    let empty = HashSet::new();
+    let mut offenders = Vec::new();
+    for older in exports {
+        let Some(newer) = complete.iter().find(|newer| newer.date > older.date) else {
+            continue;
+        };
+        let older_names = names_by_export.get(&older.date).unwrap_or(&empty);
+        let newer_names = names_by_export.get(&newer.date).unwrap_or(&empty);
+        for name in older_names.difference(newer_names).sorted() {
+            offenders.push(AssumptionOffender {
+                path_inside_zip: name.to_path_buf(),
+                detail: format!(
+                    "Present in the {} export but missing from the {} export",
+                    older.date, newer.date
+                ),
+            });
+        }
+    }
+    AssumptionResult::new(CONTAINMENT, offenders, Vec::new())
+}
+
+enum SimilarityCheck {
+    Similar,
+    Dissimilar,
+    Unverifiable,
+}
+
+/// Reports names whose differing variants are not perceptually similar.
+/// Variants that are not images cannot be compared, so they are listed as unverifiable instead.
+async fn check_similarity(
+    entries: Vec<ZipEntry>,
+    similarity: u32,
+) -> eyre::Result<AssumptionResult> {
+    let groups = entries
+        .into_iter()
+        .into_group_map_by(|entry| entry.path_inside_zip.clone())
+        .into_iter()
+        .map(|(path_inside_zip, entries)| {
+            let variants = entries
+                .into_iter()
+                .unique_by(|entry| entry.entry.crc32)
+                .collect_vec();
+            (path_inside_zip, variants)
+        })
+        .filter(|(_, variants)| variants.len() > 1)
+        .collect_vec();
+    info!("Comparing {} names with differing variants", groups.len());
+
+    let hasher_config = Arc::new(image_hasher_config());
+    let checked = track_progress(
+        groups,
+        Duration::from_millis(500),
+        |progress| info!("Spawning comparison tasks {progress}"),
+        |progress| info!("Completing comparison tasks {progress}"),
+        |_progress, elapsed| info!("Comparison complete in {elapsed}!"),
+        move |(path_inside_zip, variants): (PathInsideZip, Vec<ZipEntry>)| {
+            let hasher_config = hasher_config.clone();
+            async move {
+                let zips = variants
+                    .iter()
+                    .map(|variant| variant.path_to_zip.display())
+                    .join(", ");
+                if !is_image(&path_inside_zip) {
+                    return Ok((
+                        SimilarityCheck::Unverifiable,
+                        AssumptionOffender {
+                            detail: format!(
+                                "{} variants that are not images, in {zips}",
+                                variants.len()
+                            ),
+                            path_inside_zip: path_inside_zip.to_path_buf(),
+                        },
+                    ));
+                }
+                let Some(hashes) = hash_images(&variants, &hasher_config).await? else {
+                    return Ok((
+                        SimilarityCheck::Unverifiable,
+                        AssumptionOffender {
+                            path_inside_zip: path_inside_zip.to_path_buf(),
+                            detail: format!("Some variants could not be decoded, in {zips}"),
+                        },
+                    ));
+                };
+                let distance = max_distance(&hashes);
+                let check = if distance > similarity {
+                    SimilarityCheck::Dissimilar
+                } else {
+                    SimilarityCheck::Similar
+                };
+                Ok((
+                    check,
+                    AssumptionOffender {
+                        path_inside_zip: path_inside_zip.to_path_buf(),
+                        detail: format!(
+                            "{} variants with a perceptual distance of {distance} (threshold {similarity}), in {zips}",
+                            variants.len()
+                        ),
+                    },
+                ))
+            }
+        },
+        24,
+    )
+    .await?;
+
+    let mut offenders = Vec::new();
+    let mut unverifiable = Vec::new();
+    for (check, offender) in checked
+        .into_iter()
+        .sorted_by(|a, b| a.1.path_inside_zip.cmp(&b.1.path_inside_zip))
+    {
+        match check {
+            SimilarityCheck::Similar => {}
+            SimilarityCheck::Dissimilar => offenders.push(offender),
+            SimilarityCheck::Unverifiable => unverifiable.push(offender),
+        }
+    }
+    Ok(AssumptionResult::new(SIMILARITY, offenders, unverifiable))
+}
+
+fn print_results(
+    results: &[AssumptionResult],
+    format: OutputFormat,
+    limit: usize,
+) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(results)?),
+        OutputFormat::Csv => {
+            println!("{}", csv_row(["assumption", "passed", "path", "detail"]));
+            for result in results {
+                if result.offenders.is_empty() {
+                    println!(
+                        "{}",
+                        csv_row([
+                            result.assumption.clone(),
+                            result.passed.to_string(),
+                            String::new(),
+                            String::new(),
+                        ])
+                    );
+                }
+                for offender in &result.offenders {
+                    println!(
+                        "{}",
+                        csv_row([
+                            result.assumption.clone(),
+                            result.passed.to_string(),
+                            offender.path_inside_zip.display().to_string(),
+                            offender.detail.clone(),
+                        ])
+                    );
+                }
+            }
+        }
+        OutputFormat::Table => {
+            for result in results {
+                println!(
+                    "[{}] {}",
+                    if result.passed { "PASS" } else { "FAIL" },
+                    result.assumption
+                );
+                for offender in result.offenders.iter().take(limit) {
+                    println!(
+                        "  {}: {}",
+                        offender.path_inside_zip.display(),
+                        offender.detail
+                    );
+                }
+                if result.offenders.len() > limit {
+                    println!("  ... and {} more", result.offenders.len() - limit);
+                }
+                if !result.unverifiable.is_empty() {
+                    println!(
+                        "  {} names have differing variants that cannot be compared perceptually",
+                        result.unverifiable.len()
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,23 @@
+use super::assumptions_command::AssumptionsCommand;
+use super::diff_command::DiffCommand;
+use super::enrich_command::EnrichCommand;
+use super::export_contacts_command::ExportContactsCommand;
+use super::export_events_command::ExportEventsCommand;
+use super::export_mail_command::ExportMailCommand;
+use super::extract_command::ExtractCommand;
+use super::friends_command::FriendsCommand;
+use super::gallery_command::GalleryCommand;
+use super::history_command::HistoryCommand;
+use super::links_command::LinksCommand;
 use super::profile_command::ProfileCommand;
+use super::rebuild_command::RebuildCommand;
+use super::records_command::RecordsCommand;
+use super::render_command::RenderCommand;
+use super::retire_command::RetireCommand;
+use super::savings_command::SavingsCommand;
+use super::schema_command::SchemaCommand;
+use super::search_command::SearchCommand;
+use super::stats_command::StatsCommand;
 use super::sync_command::SyncCommand;
 use super::validate_command::ValidateCommand;
 use clap::Args;
@@ -23,6 +42,48 @@ pub enum Commands {
     Sync(SyncCommand),
     /// Audits the active profile destination directory for discrepencies with the zip file contents of the source directories
     Validate(ValidateCommand),
+    /// Reports per-extension CRC and size statistics across the active profile's source zips
+    Stats(StatsCommand),
+    /// Reports how much space the merged destination saves compared to keeping every source zip
+    Savings(SavingsCommand),
+    /// Proves source zips are fully present in the destination before archiving them
+    Retire(RetireCommand),
+    /// Reconstructs source zips from the destination and verifies every CRC32 against the original
+    Rebuild(RebuildCommand),
+    /// Checks the source zips against the rules in Assumptions.md
+    Assumptions(AssumptionsCommand),
+    /// Lists entries added, removed and changed between two export zips
+    Diff(DiffCommand),
+    /// Shows every version of a single path across the source zips
+    History(HistoryCommand),
+    /// Extracts entries selected by glob or regex from the source zips
+    Extract(ExtractCommand),
+    /// Searches message, post and comment text across every source zip
+    Search(SearchCommand),
+    /// Checks that uris in the synced JSON point at files in the destination
+    Links(LinksCommand),
+    /// Sets capture dates on synced photos and videos from Meta's JSON metadata
+    Enrich(EnrichCommand),
+    /// Builds a YYYY/MM view of photos and videos from links to the destination files
+    Gallery(GalleryCommand),
+    /// Generates a static HTML site of photos, videos and Messenger threads from the destination
+    Render(RenderCommand),
+    /// Exports Messenger threads as mbox files or one .eml file per message, with attachments embedded
+    ExportMail(ExportMailCommand),
+    /// Reports who was added to or removed from friends and followers lists across exports
+    Friends(FriendsCommand),
+    /// Lists posts, comments, messages and reactions that older exports have and the newest does not
+    Records(RecordsCommand),
+    /// Reports JSON fields added, removed or changed in type between exports
+    Schema(SchemaCommand),
+    /// Writes everyone from address books, synced contacts and friends lists across exports as one vCard file
+    ///
+    /// Contacts are merged by name alone, ignoring case, spacing and Unicode normalization.
+    /// Two people with the same name become one contact, and someone listed under different names appears once for each,
+    /// even when the entries share a phone number or email address.
+    ExportContacts(ExportContactsCommand),
+    /// Writes hosted events, invitations and responses across exports as one iCalendar file
+    ExportEvents(ExportEventsCommand),
 }
 
 #[derive(Args)]
@@ -33,6 +94,9 @@ pub struct GlobalArgs {
     /// Run in non-interactive mode
     #[clap(long)]
     pub non_interactive: bool,
+    /// Scan local file headers of zips whose central directory is missing or unreadable
+    #[clap(long)]
+    pub recover: bool,
 }
 
 impl Command {
@@ -41,6 +105,25 @@ impl Command {
             Commands::Profile(cmd) => cmd.handle(self.global_args).await,
             Commands::Sync(cmd) => cmd.handle(self.global_args).await,
             Commands::Validate(cmd) => cmd.handle(self.global_args).await,
+            Commands::Stats(cmd) => cmd.handle(self.global_args).await,
+            Commands::Savings(cmd) => cmd.handle(self.global_args).await,
+            Commands::Retire(cmd) => cmd.handle(self.global_args).await,
+            Commands::Rebuild(cmd) => cmd.handle(self.global_args).await,
+            Commands::Assumptions(cmd) => cmd.handle(self.global_args).await,
+            Commands::Diff(cmd) => cmd.handle(self.global_args).await,
+            Commands::History(cmd) => cmd.handle(self.global_args).await,
+            Commands::Extract(cmd) => cmd.handle(self.global_args).await,
+            Commands::Search(cmd) => cmd.handle(self.global_args).await,
+            Commands::Links(cmd) => cmd.handle(self.global_args).await,
+            Commands::Enrich(cmd) => cmd.handle(self.global_args).await,
+            Commands::Gallery(cmd) => cmd.handle(self.global_args).await,
+            Commands::Render(cmd) => cmd.handle(self.global_args).await,
+            Commands::ExportMail(cmd) => cmd.handle(self.global_args).await,
+            Commands::Friends(cmd) => cmd.handle(self.global_args).await,
+            Commands::Records(cmd) => cmd.handle(self.global_args).await,
+            Commands::Schema(cmd) => cmd.handle(self.global_args).await,
+            Commands::ExportContacts(cmd) => cmd.handle(self.global_args).await,
+            Commands::ExportEvents(cmd) => cmd.handle(self.global_args).await,
         }
     }
 }
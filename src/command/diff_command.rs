@@ -0,0 +1,288 @@
+use crate::command::GlobalArgs;
+use crate::export_change::ExportChange;
+use crate::export_diff_entry::ExportDiffEntry;
+use crate::meta::record_diff::diff_json_records;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::path_inside_zip::PathInsideZip;
+use crate::path_to_zip::PathToZip;
+use crate::perceptual::hash_images;
+use crate::perceptual::image_hasher_config;
+use crate::perceptual::is_image;
+use crate::perceptual::max_distance;
+use crate::progress::worker::track_progress;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
+use crate::zip_entry::ZipEntry;
+use clap::Args;
+use eyre::bail;
+use img_hash::HasherConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct DiffCommand {
+    /// The older export zip
+    pub old: PathBuf,
+    /// The newer export zip
+    pub new: PathBuf,
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct DiffReport {
+    old: PathBuf,
+    new: PathBuf,
+    added: usize,
+    removed: usize,
+    changed: usize,
+    unchanged: usize,
+    entries: Vec<ExportDiffEntry>,
+}
+
+impl DiffCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        for path in [&self.old, &self.new] {
+            if !path.is_file() {
+                bail!("Zip {} does not exist", path.display());
+            }
+        }
+        if self.old == self.new {
+            bail!("Cannot diff a zip with itself");
+        }
+        let old_zip = PathToZip::new(Arc::new(self.old.clone()));
+        let new_zip = PathToZip::new(Arc::new(self.new.clone()));
+
+        info!("Reading entries from both zips...");
+        let ReadEntries { entries, skipped } = read_entries_from_zips::read_entries_from_zips(
+            vec![old_zip.clone(), new_zip.clone()],
+            global.recover,
+        )
+        .await?;
+        // Everything would show up as added or removed against a zip that could not be read at all
+        for unreadable in skipped
+            .iter()
+            .filter(|skipped| skipped.entry_name.is_none())
+        {
+            if !entries
+                .iter()
+                .any(|entry| entry.path_to_zip == unreadable.path_to_zip)
+            {
+                bail!(
+                    "Failed to read zip {}: {}",
+                    unreadable.path_to_zip.display(),
+                    unreadable.reason
+                );
+            }
+        }
+        let (old_entries, new_entries): (Vec<ZipEntry>, Vec<ZipEntry>) = entries
+            .into_iter()
+            .partition(|entry| entry.path_to_zip == old_zip);
+        let mut old_entries = by_name(old_entries);
+        let new_entries = by_name(new_entries);
+        info!(
+            "Comparing {} entries with {} entries",
+            old_entries.len(),
+            new_entries.len()
+        );
+
+        let mut entries = Vec::new();
+        let mut changed = Vec::new();
+        let mut unchanged = 0;
+        for (path_inside_zip, new_entry) in new_entries {
+            match old_entries.remove(&path_inside_zip) {
+                None => entries.push(ExportDiffEntry {
+                    change: ExportChange::Added,
+                    path_inside_zip: path_inside_zip.to_path_buf(),
+                    old_size: None,
+                    new_size: Some(new_entry.entry.uncompressed_size),
+                    perceptual_distance: None,
+                    records: None,
+                }),
+                Some(old_entry) if old_entry.entry.crc32 == new_entry.entry.crc32 => {
+                    unchanged += 1;
+                }
+                Some(old_entry) => changed.push((old_entry, new_entry)),
+            }
+        }
+        for (path_inside_zip, old_entry) in old_entries {
+            entries.push(ExportDiffEntry {
+                change: ExportChange::Removed,
+                path_inside_zip: path_inside_zip.to_path_buf(),
+                old_size: Some(old_entry.entry.uncompressed_size),
+                new_size: None,
+                perceptual_distance: None,
+                records: None,
+            });
+        }
+
+        let hasher_config = Arc::new(image_hasher_config());
+        let changed = track_progress(
+            changed,
+            Duration::from_millis(500),
+            |progress| info!("Spawning comparison tasks {progress}"),
+            |progress| info!("Completing comparison tasks {progress}"),
+            |_progress, elapsed| info!("Comparison complete in {elapsed}!"),
+            move |(old_entry, new_entry): (ZipEntry, ZipEntry)| {
+                let hasher_config = hasher_config.clone();
+                async move { describe_change(old_entry, new_entry, &hasher_config).await }
+            },
+            24,
+        )
+        .await?;
+        entries.extend(changed);
+        entries.sort_by(|a, b| {
+            a.change
+                .cmp(&b.change)
+                .then(a.path_inside_zip.cmp(&b.path_inside_zip))
+        });
+
+        let report = DiffReport {
+            old: self.old,
+            new: self.new,
+            added: count(&entries, ExportChange::Added),
+            removed: count(&entries, ExportChange::Removed),
+            changed: count(&entries, ExportChange::Changed),
+            unchanged,
+            entries,
+        };
+        print_report(&report, self.format)?;
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// Keeps one entry per name; zips should not contain duplicate names, but if they do the first wins.
+fn by_name(entries: Vec<ZipEntry>) -> HashMap<PathInsideZip, ZipEntry> {
+    let mut rtn = HashMap::with_capacity(entries.len());
+    for entry in entries {
+        if rtn.contains_key(&entry.path_inside_zip) {
+            warn!(
+                "Duplicate entry {} in {}, keeping the first",
+                entry.path_inside_zip.display(),
+                entry.path_to_zip.display()
+            );
+            continue;
+        }
+        rtn.insert(entry.path_inside_zip.clone(), entry);
+    }
+    rtn
+}
+
+fn count(entries: &[ExportDiffEntry], change: ExportChange) -> usize {
+    entries
+        .iter()
+        .filter(|entry| entry.change == change)
+        .count()
+}
+
+async fn describe_change(
+    old_entry: ZipEntry,
+    new_entry: ZipEntry,
+    hasher_config: &HasherConfig,
+) -> eyre::Result<ExportDiffEntry> {
+    let mut rtn = ExportDiffEntry {
+        change: ExportChange::Changed,
+        path_inside_zip: new_entry.path_inside_zip.to_path_buf(),
+        old_size: Some(old_entry.entry.uncompressed_size),
+        new_size: Some(new_entry.entry.uncompressed_size),
+        perceptual_distance: None,
+        records: None,
+    };
+    if is_image(&new_entry.path_inside_zip) {
+        let pair = [old_entry, new_entry];
+        rtn.perceptual_distance = hash_images(&pair, hasher_config)
+            .await?
+            .map(|hashes| max_distance(&hashes));
+    } else if new_entry.is_json() {
+        let old = old_entry.bytes().await?;
+        let new = new_entry.bytes().await?;
+        match diff_json_records(&old, &new) {
+            Ok(records) => rtn.records = Some(records),
+            Err(e) => warn!(
+                "Failed to compare records of {}: {e}",
+                new_entry.path_inside_zip.display()
+            ),
+        }
+    }
+    Ok(rtn)
+}
+
+fn print_report(report: &DiffReport, format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "change",
+                    "path",
+                    "old_size",
+                    "new_size",
+                    "size_delta",
+                    "perceptual_distance",
+                    "records_added",
+                    "records_removed"
+                ])
+            );
+            for entry in &report.entries {
+                let optional = |value: Option<String>| value.unwrap_or_default();
+                println!(
+                    "{}",
+                    csv_row([
+                        format!("{:?}", entry.change).to_lowercase(),
+                        entry.path_inside_zip.display().to_string(),
+                        optional(entry.old_size.map(|size| size.to_string())),
+                        optional(entry.new_size.map(|size| size.to_string())),
+                        entry.size_delta().to_string(),
+                        optional(entry.perceptual_distance.map(|d| d.to_string())),
+                        optional(entry.records.as_ref().map(|r| r.added.to_string())),
+                        optional(entry.records.as_ref().map(|r| r.removed.to_string())),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            println!("Old: {}", report.old.display());
+            println!("New: {}", report.new.display());
+            println!(
+                "{} added, {} removed, {} changed, {} unchanged\n",
+                report.added, report.removed, report.changed, report.unchanged
+            );
+            for entry in &report.entries {
+                let mut line = format!(
+                    "{} {}",
+                    entry.change.symbol(),
+                    entry.path_inside_zip.display()
+                );
+                match entry.change {
+                    ExportChange::Changed => {
+                        line.push_str(&format!(
+                            "  {} -> {} ({:+})",
+                            entry.old_size.unwrap_or_default(),
+                            entry.new_size.unwrap_or_default(),
+                            entry.size_delta()
+                        ));
+                    }
+                    _ => line.push_str(&format!("  ({:+})", entry.size_delta())),
+                }
+                if let Some(distance) = entry.perceptual_distance {
+                    line.push_str(&format!("  distance={distance}"));
+                }
+                if let Some(records) = &entry.records {
+                    line.push_str(&format!("  {records}"));
+                }
+                println!("{line}");
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use crate::command::GlobalArgs;
+use crate::exif_writer::build_exif_app1;
+use crate::exif_writer::insert_exif;
+use crate::gather_existing_files::gather_existing_files;
+use crate::media_metadata_index::collect_media_metadata;
+use crate::state::profiles::Profiles;
+use chrono::DateTime;
+use chrono::Local;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use filetime::FileTime;
+use itertools::Itertools;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::debug;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct EnrichCommand {
+    /// Write EXIF DateTimeOriginal, and GPS when known, into copies of JPEG files
+    #[clap(long)]
+    pub write_exif: bool,
+    /// Where JPEG copies with EXIF are written, mirroring the destination layout. Defaults to a sibling of the destination named `<destination>_exif`
+    #[clap(long)]
+    pub exif_output: Option<PathBuf>,
+}
+
+impl EnrichCommand {
+    pub async fn handle(self, _global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let destination = app_profile.destination.clone();
+
+        info!(
+            "Gathering files from destination: {}",
+            destination.display()
+        );
+        let existing_files = gather_existing_files(&destination).await?;
+        let metadata = collect_media_metadata(&existing_files).await?;
+        info!("Found metadata for {} media files", metadata.len());
+        let files_by_name = existing_files
+            .into_iter()
+            .into_group_map_by(|file| file.path_inside_zip().to_owned());
+
+        let exif_output = self.exif_output.unwrap_or_else(|| {
+            let mut name = destination.file_name().unwrap_or_default().to_os_string();
+            name.push("_exif");
+            destination.with_file_name(name)
+        });
+        let mut touched = 0;
+        let mut exif_written = 0;
+        let mut missing = 0;
+        for (path_inside_zip, media_metadata) in metadata {
+            let Some(timestamp) = media_metadata.timestamp() else {
+                continue;
+            };
+            let Some(files) = files_by_name.get(&path_inside_zip) else {
+                missing += 1;
+                continue;
+            };
+            for file in files {
+                let path_on_disk = file.disk_path();
+                filetime::set_file_mtime(path_on_disk, FileTime::from_unix_time(timestamp, 0))
+                    .wrap_err_with(|| {
+                        format!("Failed to set modified time of {}", path_on_disk.display())
+                    })?;
+                touched += 1;
+                if self.write_exif && is_jpeg(path_on_disk) {
+                    let relative = path_on_disk.strip_prefix(&destination)?;
+                    let copy = exif_output.join(relative);
+                    if write_exif_copy(path_on_disk, &copy, timestamp, media_metadata.gps()).await?
+                    {
+                        filetime::set_file_mtime(&copy, FileTime::from_unix_time(timestamp, 0))?;
+                        exif_written += 1;
+                    }
+                }
+            }
+        }
+        info!("Set the modified time of {touched} files");
+        if self.write_exif {
+            info!(
+                "Wrote {exif_written} JPEG copies with EXIF to {}",
+                exif_output.display()
+            );
+        }
+        if missing > 0 {
+            warn!("{missing} media files referenced by JSON are not in the destination");
+        }
+        Ok(())
+    }
+}
+
+fn is_jpeg(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("jpeg"))
+}
+
+/// Writes a copy of a JPEG with EXIF inserted. Returns `false` when the file already has EXIF or is not a JPEG.
+async fn write_exif_copy(
+    source: &Path,
+    copy: &Path,
+    timestamp: i64,
+    gps: Option<(f64, f64)>,
+) -> eyre::Result<bool> {
+    let Some(taken) = DateTime::from_timestamp(timestamp, 0) else {
+        return Ok(false);
+    };
+    let taken = taken.with_timezone(&Local).naive_local();
+    let jpeg = tokio::fs::read(source).await?;
+    let Some(with_exif) = insert_exif(&jpeg, &build_exif_app1(taken, gps)) else {
+        debug!(
+            "Not writing EXIF for {}, it already has some or is not a JPEG",
+            source.display()
+        );
+        return Ok(false);
+    };
+    if let Some(parent) = copy.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(copy, with_exif)
+        .await
+        .wrap_err_with(|| format!("Failed to write {}", copy.display()))?;
+    Ok(true)
+}
@@ -0,0 +1,127 @@
+use crate::command::GlobalArgs;
+use crate::export::export_index;
+use crate::export::group_zips_by_export;
+use crate::get_zips;
+use crate::merged_contact::MergedContact;
+use crate::meta::contact::Contact;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Args)]
+pub struct ExportContactsCommand {
+    /// The vCard file to write
+    #[clap(long, default_value = "contacts.vcf")]
+    pub output: PathBuf,
+}
+
+impl ExportContactsCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let exports = group_zips_by_export(&zips).await?;
+        if exports.is_empty() {
+            bail!("No zips found in the profile sources");
+        }
+        let export_index = export_index(&exports);
+
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        // Contacts by name key, along with the index of the newest export that spelled the name that way
+        let mut contacts: HashMap<String, (usize, MergedContact)> = HashMap::new();
+        let mut files = 0;
+        for entry in entries
+            .iter()
+            .filter(|entry| Contact::is_contact_file(&entry.path_inside_zip))
+        {
+            let index = export_index[&entry.path_to_zip];
+            let date = exports[index].date;
+            let data = entry.bytes().await?;
+            let value = match serde_json::from_slice::<Value>(&data) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!(
+                        "Skipping {} in {}: {e}",
+                        entry.path_inside_zip.display(),
+                        entry.path_to_zip.display()
+                    );
+                    continue;
+                }
+            };
+            files += 1;
+            for contact in Contact::collect(&value) {
+                let (name_index, merged) =
+                    contacts.entry(name_key(&contact.name)).or_insert_with(|| {
+                        (
+                            index,
+                            MergedContact {
+                                name: contact.name.clone(),
+                                phones: BTreeSet::new(),
+                                emails: BTreeSet::new(),
+                                timestamp: None,
+                                first_export: date,
+                                last_export: date,
+                            },
+                        )
+                    });
+                if index >= *name_index {
+                    *name_index = index;
+                    merged.name = contact.name;
+                }
+                merged.phones.extend(contact.phones);
+                merged.emails.extend(contact.emails);
+                merged.timestamp = merged.timestamp.into_iter().chain(contact.timestamp).min();
+                merged.first_export = merged.first_export.min(date);
+                merged.last_export = merged.last_export.max(date);
+            }
+        }
+        info!(
+            "Read {files} contact and friends files from {} exports",
+            exports.len()
+        );
+
+        let vcards = contacts
+            .into_values()
+            .map(|(_, contact)| contact)
+            .sorted_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .map(|contact| contact.to_vcard())
+            .collect::<Vec<_>>();
+        if let Some(parent) = self.output.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&self.output, vcards.concat())
+            .await
+            .wrap_err_with(|| format!("Failed to write {}", self.output.display()))?;
+        info!(
+            "Wrote {} contacts to {}",
+            vcards.len(),
+            self.output.display()
+        );
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// Names that only differ in case, spacing or Unicode normalization belong to the same contact.
+fn name_key(name: &str) -> String {
+    name.nfc()
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .join(" ")
+}
@@ -0,0 +1,131 @@
+use crate::command::GlobalArgs;
+use crate::export::export_index;
+use crate::export::group_zips_by_export;
+use crate::get_zips;
+use crate::merged_event::MergedEvent;
+use crate::merged_event::calendar;
+use crate::meta::event::Event;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct ExportEventsCommand {
+    /// The iCalendar file to write
+    #[clap(long, default_value = "events.ics")]
+    pub output: PathBuf,
+    /// The address whose response each event's `PARTSTAT` describes
+    #[clap(long, default_value = "me@facebook.invalid")]
+    pub attendee: String,
+}
+
+impl ExportEventsCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let exports = group_zips_by_export(&zips).await?;
+        if exports.is_empty() {
+            bail!("No zips found in the profile sources");
+        }
+        let export_index = export_index(&exports);
+
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        let event_entries = entries
+            .iter()
+            .filter(|entry| Event::is_event_file(&entry.path_inside_zip))
+            .sorted_by_key(|entry| export_index[&entry.path_to_zip])
+            .collect_vec();
+        info!(
+            "Reading {} event files from {} exports",
+            event_entries.len(),
+            exports.len()
+        );
+
+        // Events by name and start time, along with the newest export that listed them
+        let mut events: HashMap<(String, i64), (usize, MergedEvent)> = HashMap::new();
+        for entry in event_entries {
+            let index = export_index[&entry.path_to_zip];
+            let date = exports[index].date;
+            let data = entry.bytes().await?;
+            let value = match serde_json::from_slice::<Value>(&data) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!(
+                        "Skipping {} in {}: {e}",
+                        entry.path_inside_zip.display(),
+                        entry.path_to_zip.display()
+                    );
+                    continue;
+                }
+            };
+            for event in Event::collect(&value) {
+                let (newest, merged) =
+                    match events.entry((event.name.clone(), event.start_timestamp)) {
+                        Entry::Vacant(vacant) => {
+                            vacant.insert((
+                                index,
+                                MergedEvent {
+                                    event,
+                                    first_export: date,
+                                    last_export: date,
+                                },
+                            ));
+                            continue;
+                        }
+                        Entry::Occupied(occupied) => occupied.into_mut(),
+                    };
+                merged.first_export = merged.first_export.min(date);
+                merged.last_export = merged.last_export.max(date);
+                // Entries are read oldest export first, and within one export a response outranks an invitation
+                let rsvp = if index == *newest {
+                    merged.event.rsvp.max(event.rsvp)
+                } else {
+                    event.rsvp
+                };
+                let old = std::mem::replace(&mut merged.event, event);
+                merged.event.rsvp = rsvp;
+                // Invitations and responses leave out the details the hosted event has
+                merged.event.end_timestamp = merged.event.end_timestamp.or(old.end_timestamp);
+                merged.event.description = merged.event.description.take().or(old.description);
+                merged.event.location = merged.event.location.take().or(old.location);
+                merged.event.geo = merged.event.geo.or(old.geo);
+                *newest = index;
+            }
+        }
+
+        let events = events
+            .into_values()
+            .map(|(_, event)| event)
+            .sorted_by(|a, b| {
+                a.event
+                    .start_timestamp
+                    .cmp(&b.event.start_timestamp)
+                    .then_with(|| a.event.name.cmp(&b.event.name))
+            })
+            .collect_vec();
+        if let Some(parent) = self.output.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&self.output, calendar(&events, &self.attendee))
+            .await
+            .wrap_err_with(|| format!("Failed to write {}", self.output.display()))?;
+        info!("Wrote {} events to {}", events.len(), self.output.display());
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
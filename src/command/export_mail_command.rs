@@ -0,0 +1,161 @@
+use crate::attachment_index::AttachmentIndex;
+use crate::command::GlobalArgs;
+use crate::conversation_files::message_files;
+use crate::conversation_files::read_conversation;
+use crate::gather_existing_files::gather_existing_files;
+use crate::mail::build_mail;
+use crate::mail::content_type;
+use crate::mail::mbox_entry;
+use crate::mail_attachment::MailAttachment;
+use crate::mail_format::MailFormat;
+use crate::meta::messages::message::Message;
+use crate::state::profiles::Profiles;
+use crate::unsafe_names::escape_unsafe_name;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufWriter;
+use tracing::info;
+
+#[derive(Args)]
+pub struct ExportMailCommand {
+    /// Write one mbox file per thread, or one `.eml` file per message
+    #[clap(long, value_enum, default_value_t = MailFormat::Mbox)]
+    pub format: MailFormat,
+    /// Directory to write to. Defaults to a sibling of the destination named `<destination>_mail`
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+    /// Only export threads whose folder name or title contains this text, ignoring case
+    #[clap(long)]
+    pub thread: Option<String>,
+}
+
+impl ExportMailCommand {
+    pub async fn handle(self, _global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let destination = std::path::absolute(&app_profile.destination)?;
+        let output = self.output.unwrap_or_else(|| {
+            let mut name = destination.file_name().unwrap_or_default().to_os_string();
+            name.push("_mail");
+            destination.with_file_name(name)
+        });
+        let output = std::path::absolute(output)?;
+        if output.starts_with(&destination) {
+            bail!(
+                "The mail output {} must not be inside the destination {}",
+                output.display(),
+                destination.display()
+            );
+        }
+
+        info!(
+            "Gathering files from destination: {}",
+            destination.display()
+        );
+        let existing_files = gather_existing_files(&destination).await?;
+        let attachments = AttachmentIndex::new(&existing_files);
+        let threads = message_files(&existing_files);
+        let filter = self.thread.map(|thread| thread.to_lowercase());
+        info!("Found {} threads", threads.len());
+
+        let mut exported_threads = 0;
+        let mut exported_messages = 0;
+        let mut embedded = 0;
+        let mut missing_attachments = 0;
+        for (thread_key, files) in threads.into_iter().sorted_by(|a, b| a.0.cmp(&b.0)) {
+            let conversation = read_conversation(thread_key, &files).await?;
+            if let Some(filter) = &filter {
+                let title = conversation.title.as_deref().unwrap_or_default();
+                if !conversation.thread_key.to_lowercase().contains(filter)
+                    && !title.to_lowercase().contains(filter)
+                {
+                    continue;
+                }
+            }
+
+            let name = escape_unsafe_name(&conversation.thread_key);
+            let mut mbox = match self.format {
+                MailFormat::Mbox => {
+                    tokio::fs::create_dir_all(&output).await?;
+                    let path = output.join(format!("{name}.mbox"));
+                    let file = tokio::fs::File::create(&path)
+                        .await
+                        .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+                    Some(BufWriter::new(file))
+                }
+                MailFormat::Eml => {
+                    tokio::fs::create_dir_all(output.join(&name)).await?;
+                    None
+                }
+            };
+            for (index, message) in conversation.messages.iter().enumerate() {
+                let (mail_attachments, missing) = read_attachments(message, &attachments).await?;
+                embedded += mail_attachments.len();
+                missing_attachments += missing.len();
+                let mail = build_mail(&conversation, message, &mail_attachments, &missing);
+                match &mut mbox {
+                    Some(mbox) => {
+                        mbox.write_all(mbox_entry(&mail, message).as_bytes())
+                            .await?
+                    }
+                    None => {
+                        let path = output
+                            .join(&name)
+                            .join(format!("{}_{index:05}.eml", message.timestamp_ms));
+                        tokio::fs::write(&path, mail)
+                            .await
+                            .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+                    }
+                }
+                exported_messages += 1;
+            }
+            if let Some(mut mbox) = mbox {
+                mbox.flush().await?;
+            }
+            exported_threads += 1;
+        }
+
+        info!(
+            "Exported {exported_messages} messages from {exported_threads} threads to {} with {embedded} attachments embedded",
+            output.display()
+        );
+        if missing_attachments > 0 {
+            info!(
+                "{missing_attachments} attachments were not found in the destination and are listed in their message body instead"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Reads every attachment of a message from the destination, returning the names of those that could not be found.
+async fn read_attachments(
+    message: &Message,
+    attachments: &AttachmentIndex,
+) -> eyre::Result<(Vec<MailAttachment>, Vec<String>)> {
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    for media in message.media() {
+        let file_name = media.file_name().to_string();
+        match attachments.resolve(media) {
+            Some(path) => {
+                let data = tokio::fs::read(path)
+                    .await
+                    .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+                found.push(MailAttachment {
+                    content_type: content_type(&file_name),
+                    file_name,
+                    data,
+                });
+            }
+            None => missing.push(file_name),
+        }
+    }
+    Ok((found, missing))
+}
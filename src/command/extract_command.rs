@@ -0,0 +1,197 @@
+use crate::canonical_variant::canonical_variant;
+use crate::command::GlobalArgs;
+use crate::export::get_export_dates;
+use crate::get_zips;
+use crate::path_inside_zip::PathInsideZip;
+use crate::perceptual::image_hasher_config;
+use crate::provenance::Provenance;
+use crate::provenance_record::ProvenanceRecord;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use crate::variant_mode::VariantMode;
+use crate::zip_entry::ZipEntry;
+use clap::Args;
+use cloud_terrastodon_user_input::Choice;
+use cloud_terrastodon_user_input::FzfArgs;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use globset::Glob;
+use globset::GlobSetBuilder;
+use itertools::Itertools;
+use regex::RegexSet;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct ExtractCommand {
+    /// Glob patterns matched against paths inside the zips, prompts to pick entries when omitted
+    pub selectors: Vec<String>,
+    /// Treat the selectors as regular expressions instead of globs
+    #[clap(long)]
+    pub regex: bool,
+    /// Which variants of each selected entry to write
+    #[clap(long, value_enum, default_value_t = VariantMode::Canonical)]
+    pub variants: VariantMode,
+    /// Zip to take variants from, required with `--variants from-zip`
+    #[clap(long)]
+    pub zip: Option<PathBuf>,
+    /// Directory to write the extracted files to, with their provenance kept in .thrumzip/provenance.json
+    #[clap(long, default_value = "extracted")]
+    pub output: PathBuf,
+}
+
+impl ExtractCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        if self.variants == VariantMode::FromZip && self.zip.is_none() {
+            bail!("--variants from-zip requires --zip");
+        }
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let export_dates = get_export_dates(&zips).await?;
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        let entries_by_name = entries
+            .into_iter()
+            .into_group_map_by(|entry| entry.path_inside_zip.clone());
+
+        let selected: HashSet<PathInsideZip> = if self.selectors.is_empty() {
+            if global.non_interactive {
+                bail!("No selectors given and running in non-interactive mode");
+            }
+            let choices = entries_by_name
+                .iter()
+                .sorted_by(|a, b| a.0.cmp(b.0))
+                .map(|(name, variants)| Choice {
+                    key: format!("{} ({})", name.display(), variants.len()),
+                    value: name.clone(),
+                })
+                .collect_vec();
+            cloud_terrastodon_user_input::pick_many(FzfArgs {
+                choices,
+                header: Some("Pick the files to extract".to_string()),
+                ..Default::default()
+            })?
+            .into_iter()
+            .map(|choice| choice.value)
+            .collect()
+        } else {
+            let matches = selector_matcher(&self.selectors, self.regex)?;
+            entries_by_name
+                .keys()
+                .filter(|name| matches(&name.to_string_lossy()))
+                .cloned()
+                .collect()
+        };
+        if selected.is_empty() {
+            bail!("No entries matched the selectors");
+        }
+        info!("Extracting {} entries", selected.len());
+
+        let from_zip = match &self.zip {
+            Some(zip) => Some(
+                std::fs::canonicalize(zip)
+                    .wrap_err_with(|| format!("Zip {} does not exist", zip.display()))?,
+            ),
+            None => None,
+        };
+        let hasher_config = image_hasher_config();
+        let sanitizer = app_profile.name_sanitizer.sanitizer();
+        let mut provenance = Provenance::load(&self.output).await?;
+        let mut written = 0;
+        for (path_inside_zip, variants) in entries_by_name
+            .into_iter()
+            .filter(|(name, _)| selected.contains(name))
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+        {
+            let to_write: Vec<(ZipEntry, bool)> = match self.variants {
+                VariantMode::All => variants
+                    .into_iter()
+                    .unique_by(|variant| variant.entry.crc32)
+                    .map(|variant| (variant, true))
+                    .collect(),
+                VariantMode::Canonical => {
+                    let (variant, ambiguous) =
+                        canonical_variant(variants, &export_dates, &hasher_config).await?;
+                    if ambiguous {
+                        warn!(
+                            "{} has variants that are not equivalent, extracting the newest one",
+                            path_inside_zip.display()
+                        );
+                    }
+                    vec![(variant, false)]
+                }
+                VariantMode::FromZip => {
+                    let found = variants
+                        .into_iter()
+                        .filter(|variant| {
+                            std::fs::canonicalize(variant.path_to_zip.as_path()).ok() == from_zip
+                        })
+                        .map(|variant| (variant, false))
+                        .collect_vec();
+                    if found.is_empty() {
+                        warn!(
+                            "{} is not in {}",
+                            path_inside_zip.display(),
+                            self.zip
+                                .as_deref()
+                                .map(|zip| zip.display().to_string())
+                                .unwrap_or_default()
+                        );
+                    }
+                    found
+                }
+            };
+            for (variant, disambiguate) in to_write {
+                let extracted =
+                    variant.get_sanitized_splat_path(&self.output, disambiguate, sanitizer)?;
+                info!("Writing {}", extracted.display());
+                variant.write_to_file(&extracted).await?;
+                provenance.record(ProvenanceRecord {
+                    extracted: extracted.strip_prefix(&self.output)?.to_path_buf(),
+                    path_inside_zip: variant.path_inside_zip.to_path_buf(),
+                    zip: variant.path_to_zip.to_path_buf(),
+                    export_date: export_dates[&variant.path_to_zip],
+                    crc32: variant.entry.crc32,
+                    modified: variant.entry.modified,
+                });
+                written += 1;
+            }
+        }
+        provenance.save(&self.output).await?;
+        info!(
+            "Extracted {written} files to {}, provenance written to {}",
+            self.output.display(),
+            Provenance::path(&self.output).display()
+        );
+
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// Tells whether a path inside a zip is selected.
+type SelectorMatcher = Box<dyn Fn(&str) -> bool>;
+
+/// Builds a predicate matching paths against any of the selectors.
+fn selector_matcher(selectors: &[String], regex: bool) -> eyre::Result<SelectorMatcher> {
+    if regex {
+        let set = RegexSet::new(selectors).wrap_err("Invalid regex selector")?;
+        return Ok(Box::new(move |path| set.is_match(path)));
+    }
+    let mut builder = GlobSetBuilder::new();
+    for selector in selectors {
+        builder.add(
+            Glob::new(selector).wrap_err_with(|| format!("Invalid glob selector {selector}"))?,
+        );
+    }
+    let set = builder.build()?;
+    Ok(Box::new(move |path| set.is_match(path)))
+}
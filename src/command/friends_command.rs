@@ -0,0 +1,240 @@
+use crate::command::GlobalArgs;
+use crate::export::export_index;
+use crate::export::group_zips_by_export;
+use crate::friend_change::FriendChange;
+use crate::friend_record::FriendRecord;
+use crate::get_zips;
+use crate::meta::connection::Connection;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Component;
+use std::path::Path;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct FriendsCommand {
+    /// Also list people who are in every export
+    #[clap(long)]
+    pub all: bool,
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct FriendsReport {
+    /// Export dates, oldest first
+    exports: Vec<NaiveDate>,
+    people: Vec<FriendRecord>,
+}
+
+impl FriendsCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let exports = group_zips_by_export(&zips).await?;
+        if exports.is_empty() {
+            bail!("No zips found in the profile sources");
+        }
+        if exports.len() < 2 {
+            warn!("Only one export found, nothing can have changed");
+        }
+        let export_index = export_index(&exports);
+
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        // An export without a list file says nothing about who is on that list
+        let mut lists: HashMap<String, Vec<bool>> = HashMap::new();
+        let mut presence: HashMap<(String, String), Vec<bool>> = HashMap::new();
+        let mut timestamps: HashMap<(String, String), (usize, i64)> = HashMap::new();
+        let mut files = 0;
+        for entry in entries
+            .iter()
+            .filter(|entry| entry.is_json() && is_friends_file(&entry.path_inside_zip))
+        {
+            let index = export_index[&entry.path_to_zip];
+            let data = entry.bytes().await?;
+            let value = match serde_json::from_slice::<Value>(&data) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!(
+                        "Skipping {} in {}: {e}",
+                        entry.path_inside_zip.display(),
+                        entry.path_to_zip.display()
+                    );
+                    continue;
+                }
+            };
+            files += 1;
+            let list = entry
+                .path_inside_zip
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            lists
+                .entry(list.clone())
+                .or_insert_with(|| vec![false; exports.len()])[index] = true;
+            for connection in Connection::collect(&value) {
+                let key = (list.clone(), connection.name);
+                presence
+                    .entry(key.clone())
+                    .or_insert_with(|| vec![false; exports.len()])[index] = true;
+                if let Some(timestamp) = connection.timestamp {
+                    let newest = timestamps.entry(key).or_insert((index, timestamp));
+                    if index >= newest.0 {
+                        *newest = (index, timestamp);
+                    }
+                }
+            }
+        }
+        info!(
+            "Read {files} friends and followers files from {} exports",
+            exports.len()
+        );
+
+        let mut people = Vec::new();
+        for (key, presence) in presence {
+            let listed_in = lists[&key.0]
+                .iter()
+                .positions(|listed| *listed)
+                .collect_vec();
+            let presence = listed_in.iter().map(|index| presence[*index]).collect_vec();
+            let Some(change) = FriendChange::classify(&presence) else {
+                continue;
+            };
+            if change == FriendChange::Unchanged && !self.all {
+                continue;
+            }
+            let first = presence.iter().position(|present| *present).unwrap_or(0);
+            let last = presence.iter().rposition(|present| *present).unwrap_or(0);
+            let timestamp = timestamps.get(&key).map(|(_, timestamp)| *timestamp);
+            let (list, name) = key;
+            people.push(FriendRecord {
+                list,
+                name,
+                change,
+                first_seen: exports[listed_in[first]].date,
+                last_seen: exports[listed_in[last]].date,
+                missing_from: (first..=last)
+                    .filter(|index| !presence[*index])
+                    .map(|index| exports[listed_in[index]].date)
+                    .collect(),
+                timestamp,
+            });
+        }
+        people.sort_by(|a, b| {
+            a.list
+                .cmp(&b.list)
+                .then(a.change.cmp(&b.change))
+                .then(a.name.cmp(&b.name))
+        });
+
+        let report = FriendsReport {
+            exports: exports.iter().map(|export| export.date).collect(),
+            people,
+        };
+        print_report(&report, self.format)?;
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// Friend, follower and friend request lists live in `friends_and_followers`, or under `connections` in newer exports.
+fn is_friends_file(path_inside_zip: &Path) -> bool {
+    path_inside_zip.components().any(|component| {
+        matches!(component, Component::Normal(name) if name == "friends_and_followers" || name == "connections")
+    })
+}
+
+fn format_timestamp(timestamp: Option<i64>) -> String {
+    timestamp
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|time| time.date_naive().to_string())
+        .unwrap_or_default()
+}
+
+fn print_report(report: &FriendsReport, format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "list",
+                    "name",
+                    "change",
+                    "first_seen",
+                    "last_seen",
+                    "missing_from",
+                    "timestamp"
+                ])
+            );
+            for person in &report.people {
+                println!(
+                    "{}",
+                    csv_row([
+                        person.list.clone(),
+                        person.name.clone(),
+                        format!("{:?}", person.change).to_lowercase(),
+                        person.first_seen.to_string(),
+                        person.last_seen.to_string(),
+                        person.missing_from.iter().join(";"),
+                        format_timestamp(person.timestamp),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            println!("Exports: {}", report.exports.iter().join(", "));
+            let counts = report.people.iter().counts_by(|person| person.change);
+            println!(
+                "{} added, {} removed, {} transient, {} intermittent\n",
+                counts.get(&FriendChange::Added).unwrap_or(&0),
+                counts.get(&FriendChange::Removed).unwrap_or(&0),
+                counts.get(&FriendChange::Transient).unwrap_or(&0),
+                counts.get(&FriendChange::Intermittent).unwrap_or(&0),
+            );
+            for (list, people) in &report.people.iter().chunk_by(|person| &person.list) {
+                println!("{list}:");
+                for person in people {
+                    let mut line = format!(
+                        "  {} {}  seen {} to {}",
+                        person.change.symbol(),
+                        person.name,
+                        person.first_seen,
+                        person.last_seen
+                    );
+                    if !person.missing_from.is_empty() {
+                        line.push_str(&format!(
+                            ", missing from {}",
+                            person.missing_from.iter().join(", ")
+                        ));
+                    }
+                    if person.timestamp.is_some() {
+                        line.push_str(&format!(", dated {}", format_timestamp(person.timestamp)));
+                    }
+                    println!("{line}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
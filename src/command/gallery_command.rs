@@ -0,0 +1,161 @@
+use crate::command::GlobalArgs;
+use crate::gallery_link_kind::GalleryLinkKind;
+use crate::gather_existing_files::gather_existing_files;
+use crate::get_zips;
+use crate::media_files::canonical_media;
+use crate::media_metadata_index::collect_media_metadata;
+use crate::path_inside_zip::PathInsideZip;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use chrono::DateTime;
+use chrono::Local;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Marks a directory as a generated gallery, so regenerating it never deletes anything else.
+const GALLERY_MARKER: &str = ".thrumzip_gallery";
+
+#[derive(Args)]
+pub struct GalleryCommand {
+    /// Directory to build the gallery in. Defaults to a sibling of the destination named `<destination>_gallery`
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+    /// How gallery entries link to the destination files
+    #[clap(long, value_enum, default_value_t = GalleryLinkKind::Hardlink)]
+    pub link: GalleryLinkKind,
+}
+
+impl GalleryCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let destination = app_profile.destination.clone();
+        let output = self.output.unwrap_or_else(|| {
+            let mut name = destination.file_name().unwrap_or_default().to_os_string();
+            name.push("_gallery");
+            destination.with_file_name(name)
+        });
+        if output.starts_with(&destination) {
+            bail!(
+                "The gallery {} must not be inside the destination {}",
+                output.display(),
+                destination.display()
+            );
+        }
+
+        info!(
+            "Gathering files from destination: {}",
+            destination.display()
+        );
+        let existing_files = gather_existing_files(&destination).await?;
+        let metadata = collect_media_metadata(&existing_files).await?;
+        let media = canonical_media(&existing_files);
+        info!("Found {} photos and videos", media.len());
+
+        info!("Reading entry dates from zips...");
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        let mut modified_by_name: HashMap<PathInsideZip, DateTime<Local>> = HashMap::new();
+        for entry in entries {
+            let modified = entry.entry.modified.with_timezone(&Local);
+            modified_by_name
+                .entry(entry.path_inside_zip)
+                .and_modify(|earliest| *earliest = (*earliest).min(modified))
+                .or_insert(modified);
+        }
+
+        reset_gallery(&output).await?;
+        let mut used_names = HashSet::new();
+        let mut linked = 0;
+        let mut from_metadata = 0;
+        for file in &media {
+            let taken = metadata
+                .get(file.path_inside_zip())
+                .and_then(|metadata| metadata.timestamp())
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .map(|taken| taken.with_timezone(&Local));
+            if taken.is_some() {
+                from_metadata += 1;
+            }
+            let Some(taken) =
+                taken.or_else(|| modified_by_name.get(file.path_inside_zip()).copied())
+            else {
+                continue;
+            };
+            let folder = output
+                .join(taken.format("%Y").to_string())
+                .join(taken.format("%m").to_string());
+            let link_path = unique_path(&folder, file.disk_path(), &mut used_names);
+            tokio::fs::create_dir_all(&folder).await?;
+            link(file.disk_path(), &link_path, self.link)
+                .await
+                .wrap_err_with(|| format!("Failed to link {}", link_path.display()))?;
+            linked += 1;
+        }
+        info!(
+            "Linked {linked} of {} files into {} ({from_metadata} dated from JSON metadata)",
+            media.len(),
+            output.display()
+        );
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// Clears a previously generated gallery, refusing to touch directories that were not made by this command.
+async fn reset_gallery(output: &Path) -> eyre::Result<()> {
+    if output.exists() {
+        if !output.join(GALLERY_MARKER).exists() {
+            bail!(
+                "{} exists and is not a gallery generated by thrumzip, refusing to replace it",
+                output.display()
+            );
+        }
+        info!("Removing previous gallery at {}", output.display());
+        tokio::fs::remove_dir_all(output).await?;
+    }
+    tokio::fs::create_dir_all(output).await?;
+    tokio::fs::write(output.join(GALLERY_MARKER), b"").await?;
+    Ok(())
+}
+
+/// Picks a file name in the folder that is not used yet, adding `~2`, `~3` and so on before the extension.
+fn unique_path(folder: &Path, source: &Path, used: &mut HashSet<PathBuf>) -> PathBuf {
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = source
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut candidate = folder.join(format!("{stem}{extension}"));
+    let mut n = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = folder.join(format!("{stem}~{n}{extension}"));
+        n += 1;
+    }
+    candidate
+}
+
+async fn link(target: &Path, link_path: &Path, kind: GalleryLinkKind) -> eyre::Result<()> {
+    match kind {
+        GalleryLinkKind::Hardlink => tokio::fs::hard_link(target, link_path).await?,
+        GalleryLinkKind::Symlink => {
+            let target = std::path::absolute(target)?;
+            #[cfg(windows)]
+            tokio::fs::symlink_file(target, link_path).await?;
+            #[cfg(not(windows))]
+            tokio::fs::symlink(target, link_path).await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::command::GlobalArgs;
+use crate::entry_coverage::EntryCoverage;
+use crate::entry_coverage::check_entry_coverage;
+use crate::entry_version::EntryVersion;
+use crate::export::get_export_dates;
+use crate::gather_existing_files::gather_existing_files;
+use crate::get_zips;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::path_inside_zip::PathInsideZip;
+use crate::perceptual::hash_image_bytes;
+use crate::perceptual::image_hasher_config;
+use crate::perceptual::is_image;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use clap::Args;
+use cloud_terrastodon_user_input::Choice;
+use cloud_terrastodon_user_input::FzfArgs;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Args)]
+pub struct HistoryCommand {
+    /// Path of the entry inside the zips, prompts to pick one when omitted
+    pub path: Option<PathBuf>,
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+impl HistoryCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let export_dates = get_export_dates(&zips).await?;
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        let mut entries_by_name = entries
+            .into_iter()
+            .into_group_map_by(|entry| entry.path_inside_zip.clone());
+
+        let path_inside_zip = match self.path {
+            Some(path) => PathInsideZip::from(path),
+            None => {
+                if global.non_interactive {
+                    bail!("No path given and running in non-interactive mode");
+                }
+                let choices = entries_by_name
+                    .iter()
+                    .sorted_by(|a, b| a.0.cmp(b.0))
+                    .map(|(name, versions)| Choice {
+                        key: format!("{} ({})", name.display(), versions.len()),
+                        value: name.clone(),
+                    })
+                    .collect_vec();
+                cloud_terrastodon_user_input::pick(FzfArgs {
+                    choices,
+                    header: Some("Pick the file to show the history of".to_string()),
+                    ..Default::default()
+                })?
+                .value
+            }
+        };
+        let Some(versions) = entries_by_name.remove(&path_inside_zip) else {
+            bail!("{} is not in any source zip", path_inside_zip.display());
+        };
+
+        let candidates = gather_existing_files(&app_profile.destination)
+            .await?
+            .into_iter()
+            .filter(|file| file.path_inside_zip() == &path_inside_zip)
+            .collect_vec();
+        let hasher_config = image_hasher_config();
+        let mut history = Vec::with_capacity(versions.len());
+        for version in versions {
+            let perceptual_hash = if is_image(&path_inside_zip) {
+                hash_image_bytes(&version.bytes().await?, &hasher_config)
+                    .map(|hash| hash.to_base64())
+            } else {
+                None
+            };
+            let coverage = check_entry_coverage(
+                &version,
+                &candidates,
+                &app_profile.destination,
+                &hasher_config,
+            )
+            .await?;
+            let (chosen, destination_file) = match coverage {
+                EntryCoverage::ByteIdentical { path_on_disk } => (true, Some(path_on_disk)),
+                EntryCoverage::Perceptual { path_on_disk, .. } => (false, Some(path_on_disk)),
+                EntryCoverage::Mismatch { .. } | EntryCoverage::Missing => (false, None),
+            };
+            history.push(EntryVersion {
+                zip: version.path_to_zip.to_path_buf(),
+                export_date: export_dates[&version.path_to_zip],
+                crc32: version.entry.crc32,
+                compressed_size: version.entry.compressed_size,
+                uncompressed_size: version.entry.uncompressed_size,
+                method: format!("{:?}", version.entry.method),
+                perceptual_hash,
+                chosen,
+                destination_file,
+            });
+        }
+        history.sort_by(|a, b| a.export_date.cmp(&b.export_date).then(a.zip.cmp(&b.zip)));
+
+        print_history(&path_inside_zip, &history, self.format)?;
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+fn print_history(
+    path_inside_zip: &PathInsideZip,
+    history: &[EntryVersion],
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(history)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "zip",
+                    "export_date",
+                    "crc32",
+                    "compressed_size",
+                    "uncompressed_size",
+                    "method",
+                    "perceptual_hash",
+                    "chosen",
+                    "destination_file"
+                ])
+            );
+            for version in history {
+                println!(
+                    "{}",
+                    csv_row([
+                        version.zip.display().to_string(),
+                        version.export_date.to_string(),
+                        format!("{:08x}", version.crc32),
+                        version.compressed_size.to_string(),
+                        version.uncompressed_size.to_string(),
+                        version.method.clone(),
+                        version.perceptual_hash.clone().unwrap_or_default(),
+                        version.chosen.to_string(),
+                        version
+                            .destination_file
+                            .as_ref()
+                            .map(|path| path.display().to_string())
+                            .unwrap_or_default(),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            println!("{} is in {} zips", path_inside_zip.display(), history.len());
+            for version in history {
+                println!(
+                    "{} {} {}",
+                    if version.chosen { "*" } else { " " },
+                    version.export_date,
+                    version.zip.display()
+                );
+                println!(
+                    "    crc32={:08x} compressed={} uncompressed={} method={}{}",
+                    version.crc32,
+                    version.compressed_size,
+                    version.uncompressed_size,
+                    version.method,
+                    version
+                        .perceptual_hash
+                        .as_ref()
+                        .map(|hash| format!(" phash={hash}"))
+                        .unwrap_or_default()
+                );
+                if let Some(destination_file) = &version.destination_file {
+                    println!("    -> {}", destination_file.display());
+                }
+            }
+            println!("* variant written to the destination by sync");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,461 @@
+use crate::command::GlobalArgs;
+use crate::existing_file::ExistingFile;
+use crate::gather_existing_files::gather_existing_files;
+use crate::link_issue::LinkIssue;
+use crate::link_status::LinkStatus;
+use crate::meta::uris::visit_uris;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::path_inside_zip::PathInsideZip;
+use crate::progress::worker::track_progress;
+use crate::sidecar::original_json_path;
+use crate::state::profiles::Profiles;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use filetime::FileTime;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct LinksCommand {
+    /// Rewrite uris of files that were only written with disambiguation to point at the disambiguated copy.
+    /// The original JSON is kept in the destination sidecar directory.
+    #[clap(long)]
+    pub rewrite: bool,
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+#[derive(Serialize, Default)]
+struct LinksReport {
+    json_files: usize,
+    uris: usize,
+    rewritten: usize,
+    issues: Vec<LinkIssue>,
+}
+
+/// The outcome of checking one JSON file.
+#[derive(Default)]
+struct CheckedFile {
+    uris: usize,
+    rewritten: usize,
+    issues: Vec<LinkIssue>,
+}
+
+impl LinksCommand {
+    pub async fn handle(self, _global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let destination = app_profile.destination.clone();
+
+        info!(
+            "Gathering files from destination: {}",
+            destination.display()
+        );
+        let existing_files = gather_existing_files(&destination).await?;
+        let json_files = existing_files
+            .iter()
+            .filter(|file| {
+                file.path_inside_zip()
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            })
+            .map(|file| {
+                (
+                    file.disk_path().clone(),
+                    file.zip_name().map(str::to_string),
+                )
+            })
+            .collect_vec();
+        let index: Arc<HashMap<PathInsideZip, Vec<ExistingFile>>> = Arc::new(
+            existing_files
+                .into_iter()
+                .into_group_map_by(|file| file.path_inside_zip().to_owned()),
+        );
+        info!("Checking uris in {} JSON files", json_files.len());
+
+        let mut report = LinksReport {
+            json_files: json_files.len(),
+            ..Default::default()
+        };
+        let rewrite = self.rewrite;
+        let checked = track_progress(
+            json_files,
+            Duration::from_millis(500),
+            |progress| info!("Enqueueing {progress}"),
+            |progress| info!("Checking {progress}"),
+            |_progress, elapsed| info!("Checked links in {elapsed}"),
+            move |(json_file, zip_name): (PathBuf, Option<String>)| {
+                let index = index.clone();
+                let destination = destination.clone();
+                async move {
+                    check_file(
+                        &json_file,
+                        zip_name.as_deref(),
+                        &index,
+                        &destination,
+                        rewrite,
+                    )
+                    .await
+                    .wrap_err_with(|| format!("Failed to check {}", json_file.display()))
+                }
+            },
+            24,
+        )
+        .await?;
+        for file in checked {
+            report.uris += file.uris;
+            report.rewritten += file.rewritten;
+            report.issues.extend(file.issues);
+        }
+        report
+            .issues
+            .sort_by(|a, b| a.json_file.cmp(&b.json_file).then(a.uri.cmp(&b.uri)));
+
+        print_report(&report, self.format)?;
+        Ok(())
+    }
+}
+
+async fn check_file(
+    json_file: &Path,
+    zip_name: Option<&str>,
+    index: &HashMap<PathInsideZip, Vec<ExistingFile>>,
+    destination: &Path,
+    rewrite: bool,
+) -> eyre::Result<CheckedFile> {
+    let data = tokio::fs::read(json_file).await?;
+    let mut value: Value = match serde_json::from_slice(&data) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!(
+                "Skipping {}, it is not valid JSON: {e}",
+                json_file.display()
+            );
+            return Ok(CheckedFile::default());
+        }
+    };
+
+    let mut rtn = CheckedFile::default();
+    rtn.rewritten = visit_uris(&mut value, &mut |uri| {
+        rtn.uris += 1;
+        let status = resolve_uri(uri, zip_name, index, destination)?;
+        let new_uri = match &status {
+            LinkStatus::Disambiguated { target } if rewrite => Some(target.clone()),
+            _ => None,
+        };
+        rtn.issues.push(LinkIssue {
+            json_file: json_file.to_path_buf(),
+            uri: uri.to_string(),
+            status,
+        });
+        new_uri
+    });
+
+    if rtn.rewritten > 0 {
+        if let Some(original) = original_json_path(destination, json_file) {
+            // Keep the bytes from the zip so coverage checks still match the CRC32
+            if !original.exists() {
+                if let Some(parent) = original.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&original, &data).await?;
+            }
+        }
+        info!(
+            "Rewriting {} uris in {}",
+            rtn.rewritten,
+            json_file.display()
+        );
+        // Keep the modification time sync gave the file
+        let modified =
+            FileTime::from_last_modification_time(&tokio::fs::metadata(json_file).await?);
+        tokio::fs::write(json_file, serde_json::to_vec_pretty(&value)?).await?;
+        filetime::set_file_mtime(json_file, modified)?;
+    }
+    Ok(rtn)
+}
+
+/// Returns `None` when the uri resolves to a file at the path it names.
+fn resolve_uri(
+    uri: &str,
+    zip_name: Option<&str>,
+    index: &HashMap<PathInsideZip, Vec<ExistingFile>>,
+    destination: &Path,
+) -> Option<LinkStatus> {
+    let candidates = index
+        .get(&PathInsideZip::from(PathBuf::from(uri)))
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if candidates.is_empty() {
+        if names_disambiguated_copy(Path::new(uri), index) {
+            return None;
+        }
+        return Some(LinkStatus::Dangling);
+    }
+    if candidates.iter().any(|candidate| !candidate.is_ambiguous()) {
+        return None;
+    }
+    let target = candidates
+        .iter()
+        .find(|candidate| zip_name.is_some() && candidate.zip_name() == zip_name)
+        .or(match candidates {
+            [single] => Some(single),
+            _ => None,
+        });
+    Some(match target {
+        Some(target) => LinkStatus::Disambiguated {
+            target: target
+                .disk_path()
+                .strip_prefix(destination)
+                .unwrap_or(target.disk_path())
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .join("/"),
+        },
+        None => LinkStatus::Ambiguous {
+            candidates: candidates
+                .iter()
+                .map(|candidate| candidate.disk_path().clone())
+                .collect(),
+        },
+    })
+}
+
+/// Whether the uri names a `<parent>/<zip>.zip/<file>` copy, as written by `--rewrite`, that exists in the destination.
+fn names_disambiguated_copy(uri: &Path, index: &HashMap<PathInsideZip, Vec<ExistingFile>>) -> bool {
+    let Some(zip_dir) = uri.parent() else {
+        return false;
+    };
+    let Some(zip_name) = zip_dir
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .filter(|name| name.ends_with(".zip"))
+    else {
+        return false;
+    };
+    let Some(file_name) = uri.file_name() else {
+        return false;
+    };
+    let logical_path = zip_dir
+        .parent()
+        .map(|parent| parent.join(file_name))
+        .unwrap_or_else(|| PathBuf::from(file_name));
+    index
+        .get(&PathInsideZip::from(logical_path))
+        .is_some_and(|candidates| {
+            candidates
+                .iter()
+                .any(|candidate| candidate.zip_name() == Some(zip_name.as_ref()))
+        })
+}
+
+fn print_report(report: &LinksReport, format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Csv => {
+            println!("{}", csv_row(["json_file", "uri", "status", "target"]));
+            for issue in &report.issues {
+                let (status, target) = match &issue.status {
+                    LinkStatus::Disambiguated { target } => ("disambiguated", target.clone()),
+                    LinkStatus::Ambiguous { candidates } => (
+                        "ambiguous",
+                        candidates.iter().map(|c| c.display()).join(";"),
+                    ),
+                    LinkStatus::Dangling => ("dangling", String::new()),
+                };
+                println!(
+                    "{}",
+                    csv_row([
+                        issue.json_file.display().to_string(),
+                        issue.uri.clone(),
+                        status.to_string(),
+                        target,
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            for issue in &report.issues {
+                match &issue.status {
+                    LinkStatus::Dangling => {
+                        println!(
+                            "dangling       {} in {}",
+                            issue.uri,
+                            issue.json_file.display()
+                        )
+                    }
+                    LinkStatus::Ambiguous { candidates } => println!(
+                        "ambiguous      {} in {} ({} candidates)",
+                        issue.uri,
+                        issue.json_file.display(),
+                        candidates.len()
+                    ),
+                    LinkStatus::Disambiguated { target } => println!(
+                        "disambiguated  {} in {} -> {target}",
+                        issue.uri,
+                        issue.json_file.display()
+                    ),
+                }
+            }
+            let count = |f: fn(&LinkStatus) -> bool| {
+                report
+                    .issues
+                    .iter()
+                    .filter(|issue| f(&issue.status))
+                    .count()
+            };
+            println!(
+                "\nChecked {} uris in {} JSON files: {} dangling, {} ambiguous, {} disambiguated, {} rewritten",
+                report.uris,
+                report.json_files,
+                count(|status| matches!(status, LinkStatus::Dangling)),
+                count(|status| matches!(status, LinkStatus::Ambiguous { .. })),
+                count(|status| matches!(status, LinkStatus::Disambiguated { .. })),
+                report.rewritten
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::check_file;
+    use super::resolve_uri;
+    use crate::existing_file::ExistingFile;
+    use crate::link_status::LinkStatus;
+    use crate::path_inside_zip::PathInsideZip;
+    use itertools::Itertools;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use uom::si::f64::Information;
+    use uom::si::information::byte;
+
+    const URI: &str = "your_facebook_activity/posts/media/photo.jpg";
+
+    fn index(zip_names: &[&str]) -> HashMap<PathInsideZip, Vec<ExistingFile>> {
+        let files = zip_names
+            .iter()
+            .map(|zip_name| ExistingFile::Ambiguous {
+                path_inside_zip: PathInsideZip::from(PathBuf::from(URI)),
+                zip_name: zip_name.to_string(),
+                path_on_disk: Path::new("/dest/your_facebook_activity/posts/media")
+                    .join(zip_name)
+                    .join("photo.jpg"),
+                size: Information::new::<byte>(1.0),
+            })
+            .collect_vec();
+        HashMap::from([(PathInsideZip::from(PathBuf::from(URI)), files)])
+    }
+
+    fn disambiguated(zip_name: &str) -> Option<LinkStatus> {
+        Some(LinkStatus::Disambiguated {
+            target: format!("your_facebook_activity/posts/media/{zip_name}/photo.jpg"),
+        })
+    }
+
+    #[test]
+    fn prefers_the_copy_from_the_same_zip() {
+        let index = index(&["a.zip", "b.zip"]);
+        assert_eq!(
+            resolve_uri(URI, Some("b.zip"), &index, Path::new("/dest")),
+            disambiguated("b.zip")
+        );
+    }
+
+    #[test]
+    fn resolves_a_single_candidate_from_another_zip() {
+        let index = index(&["a.zip"]);
+        assert_eq!(
+            resolve_uri(URI, Some("b.zip"), &index, Path::new("/dest")),
+            disambiguated("a.zip")
+        );
+        assert_eq!(
+            resolve_uri(URI, None, &index, Path::new("/dest")),
+            disambiguated("a.zip")
+        );
+    }
+
+    #[test]
+    fn leaves_several_candidates_ambiguous() {
+        let index = index(&["a.zip", "b.zip"]);
+        assert_eq!(
+            resolve_uri(URI, None, &index, Path::new("/dest")),
+            Some(LinkStatus::Ambiguous {
+                candidates: index
+                    .values()
+                    .flatten()
+                    .map(|c| c.disk_path().clone())
+                    .collect(),
+            })
+        );
+        assert_eq!(
+            resolve_uri("missing.jpg", None, &index, Path::new("/dest")),
+            Some(LinkStatus::Dangling)
+        );
+    }
+
+    #[test]
+    fn resolves_the_disambiguated_copy_a_rewrite_points_at() {
+        let index = index(&["a.zip", "b.zip"]);
+        assert_eq!(
+            resolve_uri(
+                "your_facebook_activity/posts/media/b.zip/photo.jpg",
+                None,
+                &index,
+                Path::new("/dest")
+            ),
+            None
+        );
+        assert_eq!(
+            resolve_uri(
+                "your_facebook_activity/posts/media/c.zip/photo.jpg",
+                None,
+                &index,
+                Path::new("/dest")
+            ),
+            Some(LinkStatus::Dangling)
+        );
+    }
+
+    #[tokio::test]
+    async fn rewritten_uris_pass_the_next_check() -> eyre::Result<()> {
+        let destination =
+            std::env::temp_dir().join(format!("thrumzip-links-test-{}", std::process::id()));
+        let json_file = destination.join("your_facebook_activity/posts/b.zip/posts.json");
+        tokio::fs::create_dir_all(json_file.parent().unwrap()).await?;
+        tokio::fs::write(&json_file, format!(r#"{{"media": [{{"uri": "{URI}"}}]}}"#)).await?;
+        let index = HashMap::from([(
+            PathInsideZip::from(PathBuf::from(URI)),
+            vec![ExistingFile::Ambiguous {
+                path_inside_zip: PathInsideZip::from(PathBuf::from(URI)),
+                zip_name: "b.zip".to_string(),
+                path_on_disk: destination
+                    .join("your_facebook_activity/posts/media/b.zip/photo.jpg"),
+                size: Information::new::<byte>(1.0),
+            }],
+        )]);
+
+        let rewritten = check_file(&json_file, Some("b.zip"), &index, &destination, true).await;
+        let rechecked = check_file(&json_file, Some("b.zip"), &index, &destination, false).await;
+        tokio::fs::remove_dir_all(&destination).await?;
+        assert_eq!(rewritten?.rewritten, 1);
+        let rechecked = rechecked?;
+        assert_eq!(rechecked.uris, 1);
+        assert!(rechecked.issues.is_empty());
+        Ok(())
+    }
+}
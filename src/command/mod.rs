@@ -1,8 +1,27 @@
+pub mod assumptions_command;
 #[allow(clippy::module_inception)]
 mod command;
+pub mod diff_command;
+pub mod enrich_command;
+pub mod export_contacts_command;
+pub mod export_events_command;
+pub mod export_mail_command;
+pub mod extract_command;
+pub mod friends_command;
+pub mod gallery_command;
+pub mod history_command;
+pub mod links_command;
 pub mod profile_list_command;
 pub mod profile_show_command;
 pub mod profile_use_command;
+pub mod rebuild_command;
+pub mod records_command;
+pub mod render_command;
+pub mod retire_command;
+pub mod savings_command;
+pub mod schema_command;
+pub mod search_command;
+pub mod stats_command;
 pub mod validate_command;
 pub use command::*;
 pub mod profile_add_command;
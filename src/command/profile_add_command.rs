@@ -1,4 +1,5 @@
 use crate::command::GlobalArgs;
+use crate::name_sanitizer_kind::NameSanitizerKind;
 use crate::state::profiles::DEFAULT_IMAGE_SIMILARITY_THRESHOLD;
 use crate::state::profiles::Profile;
 use crate::state::profiles::Profiles;
@@ -61,12 +62,44 @@ impl ProfileAddCommand {
             }
         };
 
+        let fix_json_encoding = {
+            let answer = prompt_line("Repair Meta's mojibake text encoding in JSON files? [y/N]: ")
+                .await
+                .wrap_err("Failed to read JSON encoding choice")?;
+            matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+        };
+
+        let detect_case_collisions = {
+            let answer = prompt_line(
+                "Warn about names that only differ by case (for Windows/macOS destinations)? [y/N]: ",
+            )
+            .await
+            .wrap_err("Failed to read case collision choice")?;
+            matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+        };
+
+        let name_sanitizer = {
+            let answer = prompt_line(
+                "Escape names that are invalid on Windows filesystems (NTFS/exFAT)? [y/N]: ",
+            )
+            .await
+            .wrap_err("Failed to read name sanitizer choice")?;
+            if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+                NameSanitizerKind::Windows
+            } else {
+                NameSanitizerKind::None
+            }
+        };
+
         // Push the new profile to the config
         profiles.profiles.push(Profile {
             destination: destination.into(),
             sources,
             similarity,
             name,
+            fix_json_encoding,
+            detect_case_collisions,
+            name_sanitizer,
         });
 
         // Save profiles
@@ -0,0 +1,295 @@
+use crate::command::GlobalArgs;
+use crate::crc_verification::verify_crc;
+use crate::entry_coverage::EntryCoverage;
+use crate::entry_coverage::check_entries_coverage;
+use crate::existing_file::ExistingFile;
+use crate::gather_existing_files::gather_existing_files;
+use crate::path_inside_zip::PathInsideZip;
+use crate::path_to_zip::PathToZip;
+use crate::perceptual::image_hasher_config;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::rebuild_issue::RebuildIssue;
+use crate::rebuild_report::RebuildReport;
+use crate::select_zips::select_zips;
+use crate::sidecar::content_path;
+use crate::sidecar::sidecar_dir;
+use crate::sidecar::write_sidecar_json;
+use crate::state::profiles::Profiles;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Timelike;
+use chrono::Utc;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::info;
+use tracing::warn;
+use zip::CompressionMethod;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+#[derive(Args)]
+pub struct RebuildCommand {
+    /// Zips to rebuild, prompts to pick from the profile sources when omitted
+    pub zips: Vec<PathBuf>,
+    /// Directory to write rebuilt zips to, defaults to the destination sidecar directory
+    #[clap(long)]
+    pub output_dir: Option<PathBuf>,
+}
+
+/// A file to store in the rebuilt zip.
+struct RebuildFile {
+    entry_name: String,
+    source: PathBuf,
+    modified: DateTime<Utc>,
+}
+
+impl RebuildCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let zips = select_zips(
+            self.zips,
+            &app_profile.sources,
+            global.non_interactive,
+            "Select zips to rebuild:",
+        )
+        .await?;
+        let output_dir = self
+            .output_dir
+            .unwrap_or_else(|| sidecar_dir(&app_profile.destination).join("rebuild"));
+        tokio::fs::create_dir_all(&output_dir).await?;
+
+        info!(
+            "Gathering files from destination: {}",
+            app_profile.destination.display()
+        );
+        let existing_destination_files: Arc<HashMap<PathInsideZip, Vec<ExistingFile>>> = Arc::new(
+            gather_existing_files(&app_profile.destination)
+                .await?
+                .into_iter()
+                .into_group_map_by(|entry| entry.path_inside_zip().to_owned()),
+        );
+        let hasher_config = Arc::new(image_hasher_config());
+
+        let mut inexact = Vec::new();
+        for zip in zips {
+            let Some(file_name) = zip.file_name() else {
+                bail!("Zip {} has no file name", zip.display());
+            };
+            let rebuilt = output_dir.join(file_name);
+            if rebuilt.exists() {
+                bail!(
+                    "{} already exists, refusing to overwrite it",
+                    rebuilt.display()
+                );
+            }
+
+            let ReadEntries { entries, skipped } =
+                read_entries_from_zips::read_entries_from_zips(vec![zip.clone()], global.recover)
+                    .await?;
+            let mut issues = skipped
+                .into_iter()
+                .map(|skipped| RebuildIssue {
+                    entry_name: skipped.entry_name.clone().unwrap_or_default(),
+                    reason: format!("Unreadable in the original zip: {}", skipped.reason),
+                })
+                .collect_vec();
+            // Keyed by raw name, distinct names can sanitize to the same path inside the zip
+            let original_entries: HashMap<String, (u32, DateTime<Utc>)> = entries
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.entry.name.clone(),
+                        (entry.entry.crc32, entry.entry.modified),
+                    )
+                })
+                .collect();
+
+            info!(
+                "Locating {} entries of {} in the destination",
+                entries.len(),
+                zip.display()
+            );
+            let records = check_entries_coverage(
+                entries,
+                existing_destination_files.clone(),
+                app_profile.destination.clone(),
+                hasher_config.clone(),
+            )
+            .await?;
+
+            let mut files = Vec::with_capacity(records.len());
+            let mut expected_crcs = HashMap::new();
+            for record in records {
+                let entry_name = record.entry_name;
+                let (crc32, modified) = original_entries[&entry_name];
+                match record.coverage {
+                    EntryCoverage::ByteIdentical { path_on_disk } => {
+                        expected_crcs.insert(entry_name.clone(), crc32);
+                        files.push(RebuildFile {
+                            entry_name,
+                            source: content_path(&app_profile.destination, &path_on_disk),
+                            modified,
+                        });
+                    }
+                    EntryCoverage::Perceptual {
+                        path_on_disk,
+                        distance,
+                    } => {
+                        // Keep the archive complete, but the bytes are not the original ones
+                        issues.push(RebuildIssue {
+                            entry_name: entry_name.clone(),
+                            reason: format!(
+                                "Resolved perceptually (distance {distance}) to {}",
+                                path_on_disk.display()
+                            ),
+                        });
+                        files.push(RebuildFile {
+                            entry_name,
+                            source: path_on_disk,
+                            modified,
+                        });
+                    }
+                    EntryCoverage::Mismatch { candidates } => issues.push(RebuildIssue {
+                        entry_name,
+                        reason: format!(
+                            "No destination file matches, candidates: {}",
+                            candidates.iter().map(|c| c.display()).join(", ")
+                        ),
+                    }),
+                    EntryCoverage::Missing => issues.push(RebuildIssue {
+                        entry_name,
+                        reason: "Missing from the destination".to_string(),
+                    }),
+                }
+            }
+
+            info!("Writing {} entries to {}", files.len(), rebuilt.display());
+            let output = rebuilt.clone();
+            tokio::task::spawn_blocking(move || write_rebuilt_zip(&output, files))
+                .await?
+                .wrap_err_with(|| format!("Failed to write {}", rebuilt.display()))?;
+
+            info!("Verifying {}", rebuilt.display());
+            let exact =
+                verify_rebuilt_zip(&rebuilt, &expected_crcs, global.recover, &mut issues).await?;
+
+            let report = RebuildReport {
+                zip: zip.to_path_buf(),
+                rebuilt: rebuilt.clone(),
+                exact,
+                issues,
+            };
+            let report_path = rebuilt.with_extension("zip.json");
+            write_sidecar_json(&report_path, &report).await?;
+            if report.is_exact() {
+                info!(
+                    "Rebuilt {} byte-exactly from the destination ({exact} entries)",
+                    zip.display()
+                );
+            } else {
+                for issue in &report.issues {
+                    warn!("{}: {}", issue.entry_name, issue.reason);
+                }
+                warn!(
+                    "{} entries of {} could not be reconstructed byte-exactly, see {}",
+                    report.issues.len(),
+                    zip.display(),
+                    report_path.display()
+                );
+                inexact.push(zip);
+            }
+        }
+
+        if !inexact.is_empty() {
+            bail!(
+                "{} zips could not be rebuilt byte-exactly:\n{}",
+                inexact.len(),
+                inexact.iter().map(|zip| zip.display()).join("\n")
+            );
+        }
+        Ok(())
+    }
+}
+
+fn write_rebuilt_zip(output: &Path, files: Vec<RebuildFile>) -> eyre::Result<()> {
+    let mut writer = ZipWriter::new(BufWriter::new(std::fs::File::create(output)?));
+    for file in files {
+        let mut source = std::fs::File::open(&file.source)
+            .wrap_err_with(|| format!("Failed to open {}", file.source.display()))?;
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(source.metadata()?.len() >= u32::MAX as u64)
+            .last_modified_time(zip_date_time(file.modified));
+        writer.start_file(file.entry_name.as_str(), options)?;
+        std::io::copy(&mut source, &mut writer)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Converts to the MS-DOS timestamp zips store, which cannot represent dates before 1980.
+fn zip_date_time(modified: DateTime<Utc>) -> zip::DateTime {
+    zip::DateTime::from_date_and_time(
+        modified.year().clamp(1980, 2107) as u16,
+        modified.month() as u8,
+        modified.day() as u8,
+        modified.hour() as u8,
+        modified.minute() as u8,
+        modified.second() as u8,
+    )
+    .unwrap_or_default()
+}
+
+/// Reads the rebuilt zip back, checking the central directory CRC32 against the original and the data against the CRC32.
+async fn verify_rebuilt_zip(
+    rebuilt: &Path,
+    expected_crcs: &HashMap<String, u32>,
+    recover: bool,
+    issues: &mut Vec<RebuildIssue>,
+) -> eyre::Result<usize> {
+    let ReadEntries { entries, skipped } = read_entries_from_zips::read_entries_from_zips(
+        vec![PathToZip::new(Arc::new(rebuilt.to_path_buf()))],
+        recover,
+    )
+    .await?;
+    if let Some(skipped) = skipped.first() {
+        bail!("Rebuilt zip is unreadable: {skipped}");
+    }
+    let rebuilt_entries = entries
+        .into_iter()
+        .map(|entry| (entry.entry.name.clone(), entry))
+        .collect::<HashMap<_, _>>();
+    let mut exact = 0;
+    for (entry_name, expected_crc) in expected_crcs.iter().sorted() {
+        let Some(rebuilt_entry) = rebuilt_entries.get(entry_name) else {
+            issues.push(RebuildIssue {
+                entry_name: entry_name.clone(),
+                reason: "Not found in the rebuilt zip".to_string(),
+            });
+            continue;
+        };
+        if rebuilt_entry.entry.crc32 != *expected_crc || !verify_crc(rebuilt_entry).await {
+            issues.push(RebuildIssue {
+                entry_name: entry_name.clone(),
+                reason: format!(
+                    "CRC32 {:08x} in the rebuilt zip differs from {:08x} in the original",
+                    rebuilt_entry.entry.crc32, expected_crc
+                ),
+            });
+            continue;
+        }
+        exact += 1;
+    }
+    Ok(exact)
+}
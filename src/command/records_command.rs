@@ -0,0 +1,317 @@
+use crate::command::GlobalArgs;
+use crate::export::export_index;
+use crate::export::group_zips_by_export;
+use crate::get_zips;
+use crate::lost_record::LostRecord;
+use crate::meta::mojibake::fix_mojibake_value;
+use crate::meta::record_kind::RecordKind;
+use crate::meta::records::is_unsent;
+use crate::meta::records::record_arrays;
+use crate::meta::records::record_identity;
+use crate::meta::records::record_summary;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::path_to_zip::PathToZip;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::record_loss::RecordLoss;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use crate::zip_entry::ZipEntry;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
+
+/// Longest record summary shown, in characters.
+const SUMMARY_LEN: usize = 80;
+
+#[derive(Args)]
+pub struct RecordsCommand {
+    /// Only check these kinds of records. Defaults to all of them
+    #[clap(long, value_enum)]
+    pub kind: Vec<RecordKind>,
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct RecordsReport {
+    /// Export dates, oldest first
+    exports: Vec<NaiveDate>,
+    deleted: usize,
+    unsent: usize,
+    file_missing: usize,
+    records: Vec<LostRecord>,
+}
+
+/// What is known about one record after reading the exports up to some date.
+struct SeenRecord {
+    last_seen: usize,
+    last_zip: PathToZip,
+    unsent: bool,
+    /// The newest export, and its zip, where the record still had its content
+    last_with_content: Option<(usize, PathToZip)>,
+    summary: String,
+    timestamp: Option<i64>,
+}
+
+impl RecordsCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let exports = group_zips_by_export(&zips).await?;
+        if exports.len() < 2 {
+            bail!(
+                "At least two exports are needed to find deleted records, found {}",
+                exports.len()
+            );
+        }
+        let export_index = export_index(&exports);
+
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        let mut groups: BTreeMap<(RecordKind, PathBuf), Vec<&ZipEntry>> = BTreeMap::new();
+        for entry in &entries {
+            let Some(kind) = RecordKind::from_path(&entry.path_inside_zip) else {
+                continue;
+            };
+            if !self.kind.is_empty() && !self.kind.contains(&kind) {
+                continue;
+            }
+            groups
+                .entry((kind, kind.group(&entry.path_inside_zip)))
+                .or_default()
+                .push(entry);
+        }
+        info!(
+            "Comparing records of {} files and threads across {} exports",
+            groups.len(),
+            exports.len()
+        );
+
+        let newest = exports.len() - 1;
+        // A record missing from the newest export may just be in an entry that could not be read
+        let newest_zips = exports[newest].zips.iter().collect::<HashSet<_>>();
+        let mut newest_unreadable = false;
+        let mut incomplete_groups = HashSet::new();
+        for skipped in skipped
+            .iter()
+            .filter(|skipped| newest_zips.contains(&skipped.path_to_zip))
+        {
+            match &skipped.entry_name {
+                None => newest_unreadable = true,
+                Some(entry_name) => {
+                    let path = PathBuf::from(entry_name);
+                    if let Some(kind) = RecordKind::from_path(&path) {
+                        incomplete_groups.insert((kind, kind.group(&path)));
+                    }
+                }
+            }
+        }
+        if newest_unreadable {
+            warn!(
+                "Part of the {} export could not be read, no losses will be reported",
+                exports[newest].date
+            );
+        }
+
+        let mut records = Vec::new();
+        for ((kind, group), mut group_entries) in groups {
+            if newest_unreadable || incomplete_groups.contains(&(kind, group.clone())) {
+                continue;
+            }
+            group_entries.sort_by_key(|entry| export_index[&entry.path_to_zip]);
+            let mut seen: HashMap<String, SeenRecord> = HashMap::new();
+            let mut exported_in = HashSet::new();
+            let mut newest_complete = true;
+            for entry in group_entries {
+                let index = export_index[&entry.path_to_zip];
+                let data = entry.bytes().await?;
+                let mut value = match serde_json::from_slice::<Value>(&data) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        warn!(
+                            "Skipping {} in {}: {e}",
+                            entry.path_inside_zip.display(),
+                            entry.path_to_zip.display()
+                        );
+                        newest_complete &= index != newest;
+                        continue;
+                    }
+                };
+                fix_mojibake_value(&mut value);
+                exported_in.insert(index);
+                for (label, items) in record_arrays(&value) {
+                    // Participants and similar lists describe the file rather than being records in it
+                    if matches!(label, "participants" | "magic_words") {
+                        continue;
+                    }
+                    for record in items {
+                        let unsent = is_unsent(record);
+                        let state =
+                            seen.entry(record_identity(record))
+                                .or_insert_with(|| SeenRecord {
+                                    last_seen: index,
+                                    last_zip: entry.path_to_zip.clone(),
+                                    unsent,
+                                    last_with_content: None,
+                                    summary: String::new(),
+                                    timestamp: record_timestamp(record),
+                                });
+                        state.last_seen = index;
+                        state.last_zip = entry.path_to_zip.clone();
+                        state.unsent = unsent;
+                        if !unsent {
+                            state.last_with_content = Some((index, entry.path_to_zip.clone()));
+                            state.summary = record_summary(record, SUMMARY_LEN);
+                        }
+                    }
+                }
+            }
+
+            if !newest_complete {
+                warn!(
+                    "Not reporting losses from {}, it could not be read from the newest export",
+                    group.display()
+                );
+                continue;
+            }
+            for state in seen.into_values() {
+                let Some(loss) = RecordLoss::classify(
+                    state.last_seen,
+                    newest,
+                    exported_in.contains(&newest),
+                    state.unsent,
+                    state.last_with_content.is_some(),
+                ) else {
+                    continue;
+                };
+                let (last_seen, last_zip) = match (loss, state.last_with_content) {
+                    (RecordLoss::Unsent, Some(last_with_content)) => last_with_content,
+                    _ => (state.last_seen, state.last_zip),
+                };
+                records.push(LostRecord {
+                    kind,
+                    loss,
+                    path_inside_zip: group.clone(),
+                    summary: state.summary,
+                    timestamp: state.timestamp,
+                    last_seen: exports[last_seen].date,
+                    last_seen_zip: last_zip.to_path_buf(),
+                });
+            }
+        }
+        records.sort_by(|a, b| {
+            a.kind
+                .cmp(&b.kind)
+                .then(a.path_inside_zip.cmp(&b.path_inside_zip))
+                .then(a.timestamp.cmp(&b.timestamp))
+        });
+
+        let count = |loss: RecordLoss| records.iter().filter(|record| record.loss == loss).count();
+        let report = RecordsReport {
+            exports: exports.iter().map(|export| export.date).collect(),
+            deleted: count(RecordLoss::Deleted),
+            unsent: count(RecordLoss::Unsent),
+            file_missing: count(RecordLoss::FileMissing),
+            records,
+        };
+        print_report(&report, self.format)?;
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// The record's own time in seconds, from `timestamp_ms` for messages or `timestamp` otherwise.
+fn record_timestamp(record: &Value) -> Option<i64> {
+    record
+        .get("timestamp_ms")
+        .and_then(Value::as_i64)
+        .map(|timestamp_ms| timestamp_ms / 1000)
+        .or_else(|| record.get("timestamp").and_then(Value::as_i64))
+}
+
+fn format_timestamp(timestamp: Option<i64>) -> String {
+    timestamp
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn print_report(report: &RecordsReport, format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "kind",
+                    "loss",
+                    "path",
+                    "timestamp",
+                    "last_seen",
+                    "last_seen_zip",
+                    "summary"
+                ])
+            );
+            for record in &report.records {
+                println!(
+                    "{}",
+                    csv_row([
+                        format!("{:?}", record.kind).to_lowercase(),
+                        format!("{:?}", record.loss).to_lowercase(),
+                        record.path_inside_zip.display().to_string(),
+                        format_timestamp(record.timestamp),
+                        record.last_seen.to_string(),
+                        record.last_seen_zip.display().to_string(),
+                        record.summary.clone(),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            println!("Exports: {}", report.exports.iter().join(", "));
+            println!(
+                "{} deleted, {} unsent, {} in files no longer exported\n",
+                report.deleted, report.unsent, report.file_missing
+            );
+            for ((kind, path), records) in &report
+                .records
+                .iter()
+                .chunk_by(|record| (record.kind, &record.path_inside_zip))
+            {
+                println!("{kind:?} {}:", path.display());
+                for record in records {
+                    println!(
+                        "  {:?} {}  last seen {} in {}  {}",
+                        record.loss,
+                        format_timestamp(record.timestamp),
+                        record.last_seen,
+                        record
+                            .last_seen_zip
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy(),
+                        record.summary
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
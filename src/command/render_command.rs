@@ -0,0 +1,205 @@
+use crate::attachment_index::AttachmentIndex;
+use crate::command::GlobalArgs;
+use crate::conversation_files::message_files;
+use crate::conversation_files::read_conversation;
+use crate::gather_existing_files::gather_existing_files;
+use crate::media_files::canonical_media;
+use crate::media_metadata_index::collect_media_metadata;
+use crate::meta::messages::message::Message;
+use crate::render_site::RenderSite;
+use crate::render_site::index_page;
+use crate::render_state::RenderState;
+use crate::render_state::source_fingerprint;
+use crate::rendered_page::RenderedPage;
+use crate::state::profiles::Profiles;
+use crate::unsafe_names::escape_unsafe_name;
+use chrono::DateTime;
+use chrono::Local;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::info;
+
+#[derive(Args)]
+pub struct RenderCommand {
+    /// Directory to write the site to. Defaults to a sibling of the destination named `<destination>_site`
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+    /// Longest side of generated thumbnails, in pixels
+    #[clap(long, default_value_t = 320)]
+    pub thumbnail_size: u32,
+    /// Render every page, even those whose source files did not change since the last run
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl RenderCommand {
+    pub async fn handle(self, _global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let destination = std::path::absolute(&app_profile.destination)?;
+        let output = self.output.unwrap_or_else(|| {
+            let mut name = destination.file_name().unwrap_or_default().to_os_string();
+            name.push("_site");
+            destination.with_file_name(name)
+        });
+        let output = std::path::absolute(output)?;
+        if output.starts_with(&destination) {
+            bail!(
+                "The site {} must not be inside the destination {}",
+                output.display(),
+                destination.display()
+            );
+        }
+
+        info!(
+            "Gathering files from destination: {}",
+            destination.display()
+        );
+        let existing_files = gather_existing_files(&destination).await?;
+        let metadata = collect_media_metadata(&existing_files).await?;
+
+        let mut months: BTreeMap<String, Vec<(DateTime<Local>, &Path)>> = BTreeMap::new();
+        for file in canonical_media(&existing_files) {
+            let taken = metadata
+                .get(file.path_inside_zip())
+                .and_then(|metadata| metadata.timestamp())
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .map(|taken| taken.with_timezone(&Local));
+            let taken = match taken {
+                Some(taken) => taken,
+                None => tokio::fs::metadata(file.disk_path())
+                    .await?
+                    .modified()?
+                    .into(),
+            };
+            months
+                .entry(taken.format("%Y-%m").to_string())
+                .or_default()
+                .push((taken, file.disk_path()));
+        }
+
+        let message_parts = message_files(&existing_files);
+
+        let site = RenderSite {
+            root: output.clone(),
+            destination: destination.clone(),
+            thumbnail_size: self.thumbnail_size,
+            attachments: AttachmentIndex::new(&existing_files),
+            thumbnail_permits: Arc::new(Semaphore::new(
+                std::thread::available_parallelism().map_or(4, |n| n.get()),
+            )),
+        };
+        let salt = format!("thumbnails {}", self.thumbnail_size);
+        let mut state = if self.force {
+            RenderState::default()
+        } else {
+            RenderState::load(&output).await?
+        };
+        let mut current_pages = HashSet::new();
+        let mut rendered = 0;
+
+        info!("Rendering {} media pages...", months.len());
+        for (month, mut items) in months {
+            items.sort();
+            let page = format!("media/{month}.html");
+            current_pages.insert(page.clone());
+            let sources = items.iter().map(|(_, path)| *path).collect_vec();
+            let fingerprint = source_fingerprint(&sources, &salt).await?;
+            if !state.is_stale(&output, &page, fingerprint) {
+                continue;
+            }
+            let title = format!("{month} ({} files)", items.len());
+            let html = site.media_page(&page, &title, &items).await;
+            write_page(&output, &page, &html).await?;
+            state
+                .pages
+                .insert(page, RenderedPage { fingerprint, title });
+            rendered += 1;
+        }
+
+        info!("Rendering {} conversations...", message_parts.len());
+        for (thread_key, files) in message_parts {
+            let page = format!("threads/{}.html", escape_unsafe_name(&thread_key));
+            current_pages.insert(page.clone());
+            let conversation = read_conversation(thread_key, &files).await?;
+            // The page shows its attachments, so it changes when one is added, replaced or goes missing
+            let mut sources = files
+                .iter()
+                .map(|file| file.disk_path().as_path())
+                .collect_vec();
+            let mut thread_salt = salt.clone();
+            for media in conversation.messages.iter().flat_map(Message::media) {
+                match site.attachments.resolve(media) {
+                    Some(path) => sources.push(path),
+                    None => {
+                        _ = write!(thread_salt, "\nmissing {}", media.uri);
+                    }
+                }
+            }
+            let fingerprint = source_fingerprint(&sources, &thread_salt).await?;
+            if !state.is_stale(&output, &page, fingerprint) {
+                continue;
+            }
+            let title = format!(
+                "{} ({} messages)",
+                conversation
+                    .title
+                    .as_deref()
+                    .unwrap_or(&conversation.thread_key),
+                conversation.messages.len()
+            );
+            let html = site.thread_page(&page, &title, &conversation).await;
+            write_page(&output, &page, &html).await?;
+            state
+                .pages
+                .insert(page, RenderedPage { fingerprint, title });
+            rendered += 1;
+        }
+
+        let removed = state
+            .pages
+            .keys()
+            .filter(|page| !current_pages.contains(*page))
+            .cloned()
+            .collect_vec();
+        for page in &removed {
+            state.pages.remove(page);
+            let path = output.join(page);
+            if path.exists() {
+                tokio::fs::remove_file(&path).await?;
+            }
+        }
+
+        write_page(&output, "index.html", &index_page(&state)).await?;
+        state.save(&output).await?;
+        info!(
+            "Rendered {rendered} of {} pages into {} ({} removed)",
+            state.pages.len(),
+            output.display(),
+            removed.len()
+        );
+        Ok(())
+    }
+}
+
+async fn write_page(site: &Path, page: &str, html: &str) -> eyre::Result<()> {
+    let path = site.join(page);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, html)
+        .await
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use crate::command::GlobalArgs;
+use crate::entry_coverage::EntryCoverage;
+use crate::entry_coverage::check_entries_coverage;
+use crate::existing_file::ExistingFile;
+use crate::gather_existing_files::gather_existing_files;
+use crate::path_inside_zip::PathInsideZip;
+use crate::perceptual::SYNC_SIMILARITY_THRESHOLD;
+use crate::perceptual::image_hasher_config;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::retire_report::RetireReport;
+use crate::select_zips::select_zips;
+use crate::sidecar::write_sidecar_json;
+use crate::size_of_thing::KnownSize;
+use crate::state::profiles::Profiles;
+use crate::zip_fingerprint::ZipFingerprint;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct RetireCommand {
+    /// Zips to retire, prompts to pick from the profile sources when omitted
+    pub zips: Vec<PathBuf>,
+    /// Move zips that are fully covered by the destination into the archive directory
+    #[clap(long)]
+    pub confirm: bool,
+    /// Where retired zips are moved, defaults to a `.retired` directory next to each zip
+    #[clap(long)]
+    pub archive_dir: Option<PathBuf>,
+}
+
+impl RetireCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+
+        let zips = select_zips(
+            self.zips,
+            &app_profile.sources,
+            global.non_interactive,
+            "Select zips to retire:",
+        )
+        .await?;
+
+        info!(
+            "Gathering files from destination: {}",
+            app_profile.destination.display()
+        );
+        let existing_destination_files: Arc<HashMap<PathInsideZip, Vec<ExistingFile>>> = Arc::new(
+            gather_existing_files(&app_profile.destination)
+                .await?
+                .into_iter()
+                .into_group_map_by(|entry| entry.path_inside_zip().to_owned()),
+        );
+        info!(
+            "Found {} files in the destination ({})",
+            existing_destination_files.len(),
+            existing_destination_files.human_size()
+        );
+
+        // Taken before reading any entries, so the reports vouch for exactly the bytes that were checked
+        info!("Fingerprinting {} zips...", zips.len());
+        let mut fingerprints = HashMap::new();
+        for zip in &zips {
+            fingerprints.insert(zip.clone(), ZipFingerprint::of(zip).await?);
+        }
+
+        info!("Reading entries from {} zips...", zips.len());
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips.clone(), global.recover).await?;
+        let mut entries_by_zip = entries
+            .into_iter()
+            .into_group_map_by(|entry| entry.path_to_zip.clone());
+        let mut skipped_by_zip = skipped
+            .into_iter()
+            .into_group_map_by(|skipped| skipped.path_to_zip.clone());
+
+        let hasher_config = Arc::new(image_hasher_config());
+        let mut blocked = Vec::new();
+        for zip in zips {
+            let zip_entries = entries_by_zip.remove(&zip).unwrap_or_default();
+            let zip_skipped = skipped_by_zip.remove(&zip).unwrap_or_default();
+            // Entries were listed at all while the zip itself was skipped only when --recover scanned its local file headers
+            let recovered = !zip_entries.is_empty()
+                && zip_skipped
+                    .iter()
+                    .any(|skipped| skipped.entry_name.is_none());
+            let unreadable = zip_skipped
+                .into_iter()
+                .map(|skipped| skipped.to_string())
+                .collect_vec();
+            info!(
+                "Checking {} entries of {} against the destination",
+                zip_entries.len(),
+                zip.display()
+            );
+            let records = check_entries_coverage(
+                zip_entries,
+                existing_destination_files.clone(),
+                app_profile.destination.clone(),
+                hasher_config.clone(),
+            )
+            .await?;
+
+            let report = RetireReport::new(
+                zip.to_path_buf(),
+                fingerprints[&zip],
+                app_profile.destination.clone(),
+                SYNC_SIMILARITY_THRESHOLD,
+                records,
+                unreadable,
+                recovered,
+            );
+            let report_path = report.path();
+            write_sidecar_json(&report_path, &report).await?;
+            let (identical, perceptual, mismatched, missing) = report.counts();
+            info!(
+                "{}: {identical} byte-identical, {perceptual} perceptually equivalent, {mismatched} mismatched, {missing} missing, {} unreadable. Report written to {}",
+                zip.display(),
+                report.unreadable.len(),
+                report_path.display()
+            );
+
+            if report.recovered {
+                warn!(
+                    "{} was read by scanning local file headers, refusing to sign it off since entries may be missing from the scan",
+                    zip.display()
+                );
+            }
+            if !report.safe_to_delete {
+                for entry in report
+                    .entries
+                    .iter()
+                    .filter(|entry| !entry.coverage.is_covered())
+                    .take(20)
+                {
+                    warn!(
+                        "Not covered: {} ({})",
+                        entry.path_inside_zip.display(),
+                        match entry.coverage {
+                            EntryCoverage::Missing => "missing",
+                            _ => "mismatch",
+                        }
+                    );
+                }
+                blocked.push(zip);
+                continue;
+            }
+
+            if self.confirm {
+                if ZipFingerprint::of(&zip).await? != report.fingerprint {
+                    warn!(
+                        "{} changed since it was checked, leaving it in place",
+                        zip.display()
+                    );
+                    blocked.push(zip);
+                    continue;
+                }
+                let archive_dir = match &self.archive_dir {
+                    Some(archive_dir) => archive_dir.clone(),
+                    None => zip
+                        .parent()
+                        .map(|parent| parent.join(".retired"))
+                        .unwrap_or_else(|| PathBuf::from(".retired")),
+                };
+                let archived = move_to_archive(&zip, &archive_dir).await?;
+                info!("Retired {} to {}", zip.display(), archived.display());
+            } else {
+                info!(
+                    "{} is safe to delete, rerun with --confirm to move it to the archive directory",
+                    zip.display()
+                );
+            }
+        }
+
+        if !blocked.is_empty() {
+            bail!(
+                "{} zips are not fully covered by the destination or changed since they were checked, and were left in place:\n{}",
+                blocked.len(),
+                blocked.iter().map(|zip| zip.display()).join("\n")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Moves a zip into the archive directory, copying when the archive is on another volume.
+async fn move_to_archive(zip: &Path, archive_dir: &Path) -> eyre::Result<PathBuf> {
+    let Some(file_name) = zip.file_name() else {
+        bail!("Zip {} has no file name", zip.display());
+    };
+    tokio::fs::create_dir_all(archive_dir).await?;
+    let target = archive_dir.join(file_name);
+    if target.exists() {
+        bail!(
+            "{} already exists, refusing to overwrite it",
+            target.display()
+        );
+    }
+    if tokio::fs::rename(zip, &target).await.is_err() {
+        tokio::fs::copy(zip, &target).await.wrap_err_with(|| {
+            format!("Failed to copy {} to {}", zip.display(), target.display())
+        })?;
+        tokio::fs::remove_file(zip).await?;
+    }
+    Ok(target)
+}
@@ -0,0 +1,247 @@
+use crate::command::GlobalArgs;
+use crate::get_zips;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::path_inside_zip::PathInsideZip;
+use crate::perceptual::SYNC_SIMILARITY_THRESHOLD;
+use crate::perceptual::hash_images;
+use crate::perceptual::image_hasher_config;
+use crate::perceptual::max_distance;
+use crate::perceptual::sync_compares_perceptually;
+use crate::progress::worker::track_progress;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::savings_report::SavingsReport;
+use crate::size_of_thing::KnownSize;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use crate::zip_contribution::ZipContribution;
+use crate::zip_entry::ZipEntry;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+use uom::si::f64::Information;
+use uom::si::information::byte;
+
+#[derive(Args)]
+pub struct SavingsCommand {
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+    /// Skip decoding images to find perceptually equivalent variants
+    #[clap(long)]
+    pub skip_perceptual: bool,
+}
+
+/// A distinct piece of content: an entry name with a specific CRC32.
+type ContentKey = (PathInsideZip, u32);
+
+impl SavingsCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+
+        info!("Gathering zip files from sources...");
+        let (zips, zips_size) = get_zips::get_zips(&app_profile.sources).await?;
+        info!(
+            "Found {} zip files in the source paths ({})",
+            zips.len(),
+            zips_size.human_size()
+        );
+
+        info!("Reading entries from zips...");
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips.clone(), global.recover).await?;
+        info!(
+            "Found {} entries ({}) in the source zips",
+            entries.len(),
+            entries.human_size()
+        );
+
+        let entries_by_content: HashMap<ContentKey, Vec<&ZipEntry>> = entries
+            .iter()
+            .into_group_map_by(|entry| (entry.path_inside_zip.clone(), entry.entry.crc32));
+
+        let redundant = if self.skip_perceptual {
+            HashSet::new()
+        } else {
+            find_perceptually_redundant(&entries_by_content).await?
+        };
+
+        let mut report = SavingsReport {
+            total_zip_bytes: zips_size.get::<byte>() as u64,
+            total_entry_bytes: entries.iter().map(|e| e.entry.uncompressed_size).sum(),
+            ..Default::default()
+        };
+        let mut needed_by_zip: HashMap<_, (u64, usize)> = HashMap::new();
+        for (key, variants) in &entries_by_content {
+            let size = variants[0].entry.uncompressed_size;
+            report.unique_content_bytes += size;
+            if redundant.contains(key) {
+                report.perceptual_dedup_bytes += size;
+                continue;
+            }
+            let zips_with_content = variants
+                .iter()
+                .map(|variant| &variant.path_to_zip)
+                .unique()
+                .collect_vec();
+            if let [only_zip] = zips_with_content.as_slice() {
+                let needed = needed_by_zip.entry((*only_zip).clone()).or_default();
+                needed.0 += size;
+                needed.1 += 1;
+            }
+        }
+        report.destination_bytes = report.unique_content_bytes - report.perceptual_dedup_bytes;
+
+        for zip in &zips {
+            let (needed_bytes, needed_entries) =
+                needed_by_zip.get(zip).copied().unwrap_or_default();
+            report.zips.push(ZipContribution {
+                zip: zip.to_path_buf(),
+                zip_bytes: tokio::fs::metadata(zip).await?.len(),
+                entry_bytes: entries
+                    .iter()
+                    .filter(|entry| &entry.path_to_zip == zip)
+                    .map(|entry| entry.entry.uncompressed_size)
+                    .sum(),
+                needed_bytes,
+                needed_entries,
+            });
+        }
+        report
+            .zips
+            .sort_by(|a, b| a.needed_bytes.cmp(&b.needed_bytes).then(a.zip.cmp(&b.zip)));
+
+        print_report(&report, self.format)?;
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// Finds image contents that sync would not write because a smaller, perceptually equivalent variant of the same name exists.
+async fn find_perceptually_redundant(
+    entries_by_content: &HashMap<ContentKey, Vec<&ZipEntry>>,
+) -> eyre::Result<HashSet<ContentKey>> {
+    let image_groups = entries_by_content
+        .iter()
+        .filter(|((path_inside_zip, _), _)| sync_compares_perceptually(path_inside_zip))
+        .map(|((path_inside_zip, _), variants)| (path_inside_zip.clone(), variants[0].clone()))
+        .into_group_map()
+        .into_iter()
+        .filter(|(_, representatives)| representatives.len() > 1)
+        .collect_vec();
+    info!(
+        "Comparing {} image names with differing variants",
+        image_groups.len()
+    );
+    let hasher_config = Arc::new(image_hasher_config());
+    let redundant = track_progress(
+        image_groups,
+        Duration::from_millis(500),
+        |progress| info!("Spawning hashing tasks {progress}"),
+        |progress| info!("Completing hashing tasks {progress}"),
+        |_progress, elapsed| info!("Hashing complete in {elapsed}!"),
+        move |(path_inside_zip, representatives): (PathInsideZip, Vec<ZipEntry>)| {
+            let hasher_config = hasher_config.clone();
+            async move {
+                let Some(hashes) = hash_images(&representatives, &hasher_config).await? else {
+                    return Ok(Vec::new());
+                };
+                if max_distance(&hashes) > SYNC_SIMILARITY_THRESHOLD {
+                    return Ok(Vec::new());
+                }
+                // Sync keeps the smallest variant, so every other one is redundant
+                Ok(representatives
+                    .iter()
+                    .sorted_by_key(|entry| entry.entry.uncompressed_size)
+                    .skip(1)
+                    .map(|entry| (path_inside_zip.clone(), entry.entry.crc32))
+                    .collect_vec())
+            }
+        },
+        24,
+    )
+    .await?;
+    Ok(redundant.into_iter().flatten().collect())
+}
+
+fn human(bytes: u64) -> String {
+    Information::new::<byte>(bytes as f64).human_size()
+}
+
+fn print_report(report: &SavingsReport, format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "zip",
+                    "zip_bytes",
+                    "entry_bytes",
+                    "needed_bytes",
+                    "needed_entries"
+                ])
+            );
+            for zip in &report.zips {
+                println!(
+                    "{}",
+                    csv_row([
+                        zip.zip.display().to_string(),
+                        zip.zip_bytes.to_string(),
+                        zip.entry_bytes.to_string(),
+                        zip.needed_bytes.to_string(),
+                        zip.needed_entries.to_string(),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            println!("Total zip bytes:         {}", human(report.total_zip_bytes));
+            println!(
+                "Total entry bytes:       {}",
+                human(report.total_entry_bytes)
+            );
+            println!(
+                "Unique content bytes:    {}",
+                human(report.unique_content_bytes)
+            );
+            println!(
+                "Perceptual dedup saves:  {}",
+                human(report.perceptual_dedup_bytes)
+            );
+            println!(
+                "Destination needs:       {}",
+                human(report.destination_bytes)
+            );
+            println!(
+                "Duplicated across zips:  {}",
+                human(report.total_entry_bytes - report.destination_bytes)
+            );
+            println!("\nBytes still needed per source zip:");
+            for zip in &report.zips {
+                println!(
+                    "  {}: {} on disk, needs {} in {} entries{}",
+                    zip.zip.display(),
+                    human(zip.zip_bytes),
+                    human(zip.needed_bytes),
+                    zip.needed_entries,
+                    if zip.contributes_nothing_new() {
+                        " (contributes nothing new)"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+    }
+    Ok(())
+}
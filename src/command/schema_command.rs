@@ -0,0 +1,279 @@
+use crate::command::GlobalArgs;
+use crate::export::export_index;
+use crate::export::group_zips_by_export;
+use crate::get_zips;
+use crate::meta::json_schema::JsonSchema;
+use crate::meta::json_schema::schema_pattern;
+use crate::meta::json_type::JsonType;
+use crate::meta::schema_change_kind::SchemaChangeKind;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::path_to_zip::PathToZip;
+use crate::progress::worker::track_progress;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::schema_drift::SchemaDrift;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use crate::zip_entry::ZipEntry;
+use chrono::NaiveDate;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use eyre::bail;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+use tracing::debug;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct SchemaCommand {
+    /// Only report path patterns containing this text
+    pub filter: Option<String>,
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct SchemaReport {
+    /// Export dates, oldest first
+    exports: Vec<NaiveDate>,
+    patterns: usize,
+    /// Patterns whose files first appear after the oldest export, with the date they appear
+    new_patterns: BTreeMap<String, NaiveDate>,
+    /// Patterns whose files are missing from the newest export, with the last date they were seen
+    gone_patterns: BTreeMap<String, NaiveDate>,
+    drifts: Vec<SchemaDrift>,
+}
+
+impl SchemaCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let exports = group_zips_by_export(&zips).await?;
+        if exports.is_empty() {
+            bail!("No zips found in the profile sources");
+        }
+        let export_index = export_index(&exports);
+
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
+        let json_entries = entries
+            .into_iter()
+            .filter(|entry| entry.is_json())
+            .filter(|entry| {
+                self.filter.as_ref().is_none_or(|filter| {
+                    schema_pattern(&entry.path_inside_zip).contains(filter.as_str())
+                })
+            })
+            .collect_vec();
+        info!("Inferring the schema of {} JSON files", json_entries.len());
+        let schemas = track_progress(
+            json_entries,
+            Duration::from_millis(500),
+            |progress| info!("Spawning schema tasks {progress}"),
+            |progress| info!("Completing schema tasks {progress}"),
+            |_progress, elapsed| info!("Schemas inferred in {elapsed}!"),
+            infer_schema,
+            24,
+        )
+        .await?;
+
+        // A pattern seen only partly in an export would look like drift, so those exports are left out for it
+        let mut unreadable_exports = HashSet::new();
+        let mut incomplete = HashSet::new();
+        for skipped in &skipped {
+            let Some(&export) = export_index.get(&skipped.path_to_zip) else {
+                continue;
+            };
+            match &skipped.entry_name {
+                None => {
+                    unreadable_exports.insert(export);
+                }
+                Some(entry_name) => {
+                    incomplete.insert((schema_pattern(Path::new(entry_name)), export));
+                }
+            }
+        }
+        for export in &unreadable_exports {
+            warn!(
+                "Part of the {} export could not be read, it is left out of the comparison",
+                exports[*export].date
+            );
+        }
+        let excluded = |pattern: &str, export: usize| {
+            unreadable_exports.contains(&export)
+                || incomplete.contains(&(pattern.to_string(), export))
+        };
+
+        // Pattern, then export, to the combined schema of every matching file in that export
+        let mut by_pattern: BTreeMap<String, BTreeMap<usize, JsonSchema>> = BTreeMap::new();
+        for (path_to_zip, pattern, schema) in schemas.into_iter().flatten() {
+            let export = export_index[&path_to_zip];
+            if excluded(&pattern, export) {
+                debug!(
+                    "Leaving {pattern} in the {} export out, it has skipped entries",
+                    exports[export].date
+                );
+                continue;
+            }
+            by_pattern
+                .entry(pattern)
+                .or_default()
+                .entry(export)
+                .or_default()
+                .merge(schema);
+        }
+
+        let newest = exports.len() - 1;
+        let mut new_patterns = BTreeMap::new();
+        let mut gone_patterns = BTreeMap::new();
+        let mut drifts = Vec::new();
+        for (pattern, schemas) in &by_pattern {
+            let (first, last) = match (schemas.keys().next(), schemas.keys().next_back()) {
+                (Some(first), Some(last)) => (*first, *last),
+                _ => continue,
+            };
+            // Only reported when no export it could be missing from was left out
+            if first > 0 && (0..first).all(|export| !excluded(pattern, export)) {
+                new_patterns.insert(pattern.clone(), exports[first].date);
+            }
+            if last < newest && (last + 1..=newest).all(|export| !excluded(pattern, export)) {
+                gone_patterns.insert(pattern.clone(), exports[last].date);
+            }
+            for ((old_index, old), (new_index, new)) in schemas.iter().tuple_windows() {
+                let changes = JsonSchema::diff(old, new);
+                if changes.is_empty() {
+                    continue;
+                }
+                drifts.push(SchemaDrift {
+                    pattern: pattern.clone(),
+                    old_export: exports[*old_index].date,
+                    new_export: exports[*new_index].date,
+                    changes,
+                });
+            }
+        }
+
+        let report = SchemaReport {
+            exports: exports.iter().map(|export| export.date).collect(),
+            patterns: by_pattern.len(),
+            new_patterns,
+            gone_patterns,
+            drifts,
+        };
+        print_report(&report, self.format)?;
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+/// Reads one JSON entry, returning `None` when it is not valid JSON.
+async fn infer_schema(entry: ZipEntry) -> eyre::Result<Option<(PathToZip, String, JsonSchema)>> {
+    let data = entry.bytes().await?;
+    let value = match serde_json::from_slice::<Value>(&data) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!(
+                "Skipping {} in {}: {e}",
+                entry.path_inside_zip.display(),
+                entry.path_to_zip.display()
+            );
+            return Ok(None);
+        }
+    };
+    let pattern = schema_pattern(&entry.path_inside_zip);
+    debug!(
+        "Inferred schema of {} as {pattern}",
+        entry.path_inside_zip.display()
+    );
+    Ok(Some((
+        entry.path_to_zip,
+        pattern,
+        JsonSchema::from_value(&value),
+    )))
+}
+
+fn print_report(report: &SchemaReport, format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "pattern",
+                    "old_export",
+                    "new_export",
+                    "field",
+                    "change",
+                    "old_types",
+                    "new_types"
+                ])
+            );
+            for drift in &report.drifts {
+                for change in &drift.changes {
+                    println!(
+                        "{}",
+                        csv_row([
+                            drift.pattern.clone(),
+                            drift.old_export.to_string(),
+                            drift.new_export.to_string(),
+                            change.field.clone(),
+                            format!("{:?}", change.change).to_lowercase(),
+                            change.old_types.iter().map(|t| t.as_str()).join("|"),
+                            change.new_types.iter().map(|t| t.as_str()).join("|"),
+                        ])
+                    );
+                }
+            }
+        }
+        OutputFormat::Table => {
+            println!("Exports: {}", report.exports.iter().join(", "));
+            println!(
+                "{} path patterns, {} changed between exports\n",
+                report.patterns,
+                report
+                    .drifts
+                    .iter()
+                    .map(|drift| &drift.pattern)
+                    .unique()
+                    .count()
+            );
+            for (pattern, date) in &report.new_patterns {
+                println!("+ {pattern}  first exported {date}");
+            }
+            for (pattern, date) in &report.gone_patterns {
+                println!("- {pattern}  last exported {date}");
+            }
+            for drift in &report.drifts {
+                println!(
+                    "\n{}  {} -> {}",
+                    drift.pattern, drift.old_export, drift.new_export
+                );
+                for change in &drift.changes {
+                    let types = |types: &[JsonType]| types.iter().map(|t| t.as_str()).join("|");
+                    let detail = match change.change {
+                        SchemaChangeKind::Added => types(&change.new_types),
+                        SchemaChangeKind::Removed => types(&change.old_types),
+                        SchemaChangeKind::TypeChanged => format!(
+                            "{} -> {}",
+                            types(&change.old_types),
+                            types(&change.new_types)
+                        ),
+                    };
+                    println!("  {} {}: {detail}", change.change.symbol(), change.field);
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,272 @@
+use crate::command::GlobalArgs;
+use crate::get_zips;
+use crate::meta::mojibake::fix_mojibake_value;
+use crate::meta::text_kind::TextKind;
+use crate::meta::text_record::TextRecord;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::progress::worker::track_progress;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::search_hit::SearchHit;
+use crate::search_index::SearchIndex;
+use crate::search_query::SearchQuery;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use crate::zip_entry::ZipEntry;
+use chrono::DateTime;
+use chrono::Days;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::TimeZone;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::info;
+use tracing::warn;
+
+#[derive(Args)]
+pub struct SearchCommand {
+    /// Text to search for
+    pub query: String,
+    /// Only search one kind of record
+    #[clap(long, value_enum)]
+    pub kind: Option<TextKind>,
+    /// Only records whose sender contains this text
+    #[clap(long)]
+    pub sender: Option<String>,
+    /// Only records whose thread or title contains this text
+    #[clap(long)]
+    pub thread: Option<String>,
+    /// Only records on or after this date (YYYY-MM-DD)
+    #[clap(long)]
+    pub since: Option<NaiveDate>,
+    /// Only records on or before this date (YYYY-MM-DD)
+    #[clap(long)]
+    pub until: Option<NaiveDate>,
+    /// Maximum number of results
+    #[clap(long, default_value_t = 20)]
+    pub limit: usize,
+    /// Index every zip again instead of only new or changed ones
+    #[clap(long)]
+    pub reindex: bool,
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+impl SearchCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+        let index_path = SearchIndex::path(&app_profile.destination);
+
+        let (zips, _) = get_zips::get_zips(&app_profile.sources).await?;
+        let mut zip_keys = Vec::with_capacity(zips.len());
+        for zip in &zips {
+            let bytes = tokio::fs::metadata(zip).await?.len() as i64;
+            zip_keys.push((zip.clone(), zip.to_string_lossy().to_string(), bytes));
+        }
+        let indexed = {
+            let index_path = index_path.clone();
+            tokio::task::spawn_blocking(move || SearchIndex::open(&index_path)?.indexed_zips())
+                .await??
+        };
+        let to_index = zip_keys
+            .iter()
+            .filter(|(_, key, bytes)| self.reindex || !indexed.contains(&(key.clone(), *bytes)))
+            .map(|(zip, _, _)| zip.clone())
+            .collect_vec();
+
+        let mut records_by_zip = Vec::new();
+        if !to_index.is_empty() {
+            info!("Indexing text from {} zips...", to_index.len());
+            let ReadEntries { entries, skipped } =
+                read_entries_from_zips::read_entries_from_zips(to_index, global.recover).await?;
+            let text_entries = entries
+                .into_iter()
+                .filter(|entry| TextKind::from_path(&entry.path_inside_zip).is_some())
+                .collect_vec();
+            let extracted = track_progress(
+                text_entries,
+                Duration::from_millis(500),
+                |progress| info!("Spawning indexing tasks {progress}"),
+                |progress| info!("Completing indexing tasks {progress}"),
+                |_progress, elapsed| info!("Text extraction complete in {elapsed}!"),
+                |entry: ZipEntry| async move {
+                    let records = extract_records(&entry).await;
+                    Ok((entry, records))
+                },
+                24,
+            )
+            .await?;
+            let extracted = extracted
+                .into_iter()
+                .into_group_map_by(|(entry, _)| entry.path_to_zip.clone());
+            let incomplete = skipped
+                .iter()
+                .map(|skipped| &skipped.path_to_zip)
+                .collect::<HashSet<_>>();
+            for (zip, key, bytes) in &zip_keys {
+                if !self.reindex && indexed.contains(&(key.clone(), *bytes)) {
+                    continue;
+                }
+                let records = extracted
+                    .get(zip)
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|(entry, records)| {
+                        records
+                            .iter()
+                            .map(|record| (entry.path_inside_zip.to_path_buf(), record.clone()))
+                    })
+                    .collect_vec();
+                records_by_zip.push((key.clone(), *bytes, records, !incomplete.contains(zip)));
+            }
+            report_skipped(&skipped);
+        }
+
+        let query = SearchQuery {
+            text: self.query,
+            kind: self.kind,
+            sender: self.sender,
+            thread: self.thread,
+            since_ms: self.since.map(start_of_day_ms),
+            until_ms: self
+                .until
+                .and_then(|until| until.checked_add_days(Days::new(1)))
+                .map(start_of_day_ms),
+            limit: self.limit,
+        };
+        let current_zips: HashSet<String> = zip_keys.into_iter().map(|(_, key, _)| key).collect();
+        let hits = tokio::task::spawn_blocking(move || {
+            let mut index = SearchIndex::open(&index_path)?;
+            let mut changed = index.remove_zips_except(&current_zips)?;
+            for (zip, bytes, records, complete) in &records_by_zip {
+                info!("Indexed {} text records from {zip}", records.len());
+                if !complete {
+                    warn!("{zip} has skipped entries, it will be indexed again by the next search");
+                }
+                index.index_zip(zip, *bytes, records, *complete)?;
+                changed = true;
+            }
+            if changed || index.fts_index_missing()? {
+                index.rebuild_fts()?;
+            }
+            index.search(&query)
+        })
+        .await??;
+
+        print_hits(&hits, self.format)?;
+        Ok(())
+    }
+}
+
+/// Reads and parses one JSON entry, repairing its text encoding. Files that fail to parse are logged and skipped.
+async fn extract_records(entry: &ZipEntry) -> Vec<TextRecord> {
+    let Some(kind) = TextKind::from_path(&entry.path_inside_zip) else {
+        return Vec::new();
+    };
+    let parsed = match entry.bytes().await {
+        Ok(data) => serde_json::from_slice::<Value>(&data).map_err(eyre::Error::from),
+        Err(e) => Err(e.into()),
+    };
+    match parsed {
+        Ok(mut value) => {
+            fix_mojibake_value(&mut value);
+            TextRecord::extract(kind, &value)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to read {} from {}: {e}",
+                entry.path_inside_zip.display(),
+                entry.path_to_zip.display()
+            );
+            Vec::new()
+        }
+    }
+}
+
+fn start_of_day_ms(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_time(Default::default()))
+        .earliest()
+        .map(|start| start.timestamp_millis())
+        .unwrap_or_default()
+}
+
+fn format_timestamp(timestamp_ms: Option<i64>) -> String {
+    timestamp_ms
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|timestamp| {
+            timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn print_hits(hits: &[SearchHit], format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(hits)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "kind",
+                    "timestamp",
+                    "sender",
+                    "thread",
+                    "text",
+                    "path",
+                    "zips"
+                ])
+            );
+            for hit in hits {
+                println!(
+                    "{}",
+                    csv_row([
+                        hit.kind.clone(),
+                        format_timestamp(hit.timestamp_ms),
+                        hit.sender.clone().unwrap_or_default(),
+                        hit.thread.clone().unwrap_or_default(),
+                        hit.text.clone(),
+                        hit.path_inside_zip.display().to_string(),
+                        hit.zips.iter().map(|zip| zip.display()).join(";"),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            if hits.is_empty() {
+                println!("No results");
+            }
+            for hit in hits {
+                println!(
+                    "[{}] {} {}{}",
+                    hit.kind,
+                    format_timestamp(hit.timestamp_ms),
+                    hit.sender.as_deref().unwrap_or("unknown"),
+                    hit.thread
+                        .as_deref()
+                        .map(|thread| format!(" in {thread}"))
+                        .unwrap_or_default()
+                );
+                for line in hit.text.lines() {
+                    println!("    {line}");
+                }
+                println!("    {}", hit.path_inside_zip.display());
+                for zip in &hit.zips {
+                    println!("      from {}", zip.display());
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use crate::command::GlobalArgs;
+use crate::crc_verification::CrcVerification;
+use crate::crc_verification::verify_crc_sample;
+use crate::export::get_export_dates;
+use crate::extension_stats::ExtensionStats;
+use crate::get_zips;
+use crate::output_format::OutputFormat;
+use crate::output_format::csv_row;
+use crate::read_entries::ReadEntries;
+use crate::read_entries_from_zips;
+use crate::size_of_thing::KnownSize;
+use crate::skipped_entry::report_skipped;
+use crate::state::profiles::Profiles;
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use tracing::info;
+
+#[derive(Args)]
+pub struct StatsCommand {
+    /// Output format
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+    /// Number of random entries per zip to read back and check against their stored CRC32
+    #[clap(long, default_value_t = 0)]
+    pub verify_sample: usize,
+}
+
+#[derive(Serialize)]
+struct StatsReport {
+    zips: usize,
+    extensions: Vec<ExtensionStats>,
+    verification: Option<CrcVerification>,
+}
+
+impl StatsCommand {
+    pub async fn handle(self, global: GlobalArgs) -> eyre::Result<()> {
+        info!("Loading profile...");
+        let app_profile = Profiles::load_and_get_active_profile()
+            .await
+            .wrap_err("Failed to load active profile")?;
+
+        info!("Gathering zip files from sources...");
+        let (zips, zips_size) = get_zips::get_zips(&app_profile.sources).await?;
+        info!(
+            "Found {} zip files in the source paths ({})",
+            zips.len(),
+            zips_size.human_size()
+        );
+        let export_dates = get_export_dates(&zips).await?;
+
+        info!("Reading entries from zips...");
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips.clone(), global.recover).await?;
+        info!(
+            "Found {} entries ({}) in the source zips",
+            entries.len(),
+            entries.human_size()
+        );
+
+        let mut stats_by_extension: HashMap<String, ExtensionStats> = HashMap::new();
+        let entries_by_name = entries
+            .iter()
+            .into_group_map_by(|entry| entry.path_inside_zip.clone());
+        for (path_inside_zip, variants) in &entries_by_name {
+            let extension = path_inside_zip
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let stats = stats_by_extension
+                .entry(extension.clone())
+                .or_insert_with(|| ExtensionStats {
+                    extension,
+                    ..Default::default()
+                });
+            stats.count += 1;
+            stats.crc_zeros += variants.iter().filter(|v| v.entry.crc32 == 0).count();
+            for (older, newer) in variants.iter().tuple_combinations() {
+                let (older_crc, newer_crc) = (older.entry.crc32, newer.entry.crc32);
+                if older_crc != 0 && newer_crc != 0 {
+                    if older_crc == newer_crc {
+                        stats.crc_matches += 1;
+                    } else {
+                        stats.crc_mismatches += 1;
+                    }
+                }
+                let older_date = export_dates.get(&older.path_to_zip);
+                let newer_date = export_dates.get(&newer.path_to_zip);
+                let (older, newer) = match older_date.cmp(&newer_date) {
+                    Ordering::Less => (older, newer),
+                    Ordering::Greater => (newer, older),
+                    // Parts of the same export say nothing about change over time
+                    Ordering::Equal => continue,
+                };
+                match newer
+                    .entry
+                    .uncompressed_size
+                    .cmp(&older.entry.uncompressed_size)
+                {
+                    Ordering::Greater => stats.newer_larger += 1,
+                    Ordering::Less => stats.newer_smaller += 1,
+                    Ordering::Equal => stats.newer_equal += 1,
+                }
+            }
+        }
+
+        let verification = if self.verify_sample > 0 {
+            info!(
+                "Verifying CRC32 of {} random entries per zip...",
+                self.verify_sample
+            );
+            Some(verify_crc_sample(&entries, self.verify_sample).await?)
+        } else {
+            None
+        };
+
+        let report = StatsReport {
+            zips: zips.len(),
+            extensions: stats_by_extension
+                .into_values()
+                .sorted_by(|a, b| b.count.cmp(&a.count).then(a.extension.cmp(&b.extension)))
+                .collect(),
+            verification,
+        };
+        print_report(&report, self.format)?;
+        report_skipped(&skipped);
+        Ok(())
+    }
+}
+
+fn print_report(report: &StatsReport, format: OutputFormat) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                csv_row([
+                    "extension",
+                    "count",
+                    "crc_matches",
+                    "crc_mismatches",
+                    "crc_zeros",
+                    "newer_larger",
+                    "newer_smaller",
+                    "newer_equal",
+                ])
+            );
+            for s in &report.extensions {
+                println!(
+                    "{}",
+                    csv_row([
+                        s.extension.clone(),
+                        s.count.to_string(),
+                        s.crc_matches.to_string(),
+                        s.crc_mismatches.to_string(),
+                        s.crc_zeros.to_string(),
+                        s.newer_larger.to_string(),
+                        s.newer_smaller.to_string(),
+                        s.newer_equal.to_string(),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Table => {
+            println!("Found {} zip files", report.zips);
+            println!("Stats by extension:");
+            for s in &report.extensions {
+                println!(
+                    "{}: count={} | CRC(matches={} mismatches={} zeros={}) | SIZE(>={} <={} =={})",
+                    s.extension,
+                    s.count,
+                    s.crc_matches,
+                    s.crc_mismatches,
+                    s.crc_zeros,
+                    s.newer_larger,
+                    s.newer_smaller,
+                    s.newer_equal
+                );
+            }
+            if let Some(verification) = &report.verification {
+                println!("\nValidation summary:");
+                println!("  Checked entries: {}", verification.checked);
+                println!("  Passes:          {}", verification.passed);
+                println!("  Failures:        {}", verification.failed);
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,25 +1,32 @@
+use crate::case_collisions::find_case_collisions;
 use crate::command::GlobalArgs;
 use crate::gather_existing_files::gather_existing_files;
 use crate::get_zips;
+use crate::name_mapping::NameMapping;
 use crate::path_inside_zip::PathInsideZip;
+use crate::perceptual::SYNC_SIMILARITY_THRESHOLD;
+use crate::perceptual::hash_images;
+use crate::perceptual::image_hasher_config;
+use crate::perceptual::max_distance;
+use crate::perceptual::sync_compares_perceptually;
 use crate::progress::worker::track_progress;
+use crate::read_entries::ReadEntries;
 use crate::read_entries_from_zips;
+use crate::sidecar::original_json_path;
 use crate::size_of_thing::KnownCount;
 use crate::size_of_thing::KnownSize;
+use crate::skipped_entry::report_skipped;
 use crate::state::profiles::Profiles;
+use crate::unsafe_names::UnsafeNameMapping;
 use crate::zip_entry::ZipEntry;
 use clap::Args;
 use color_eyre::eyre::Result;
 use color_eyre::eyre::WrapErr;
-use image::load_from_memory;
-use img_hash::HashAlg;
-use img_hash::HasherConfig;
 use itertools::Itertools;
 use std::collections::HashMap;
-use std::collections::HashSet;
-use std::ffi::OsString;
 use std::sync::Arc;
 use std::time::Duration;
+use tracing::debug;
 use tracing::info;
 use tracing::warn;
 
@@ -27,7 +34,7 @@ use tracing::warn;
 pub struct SyncCommand;
 
 impl SyncCommand {
-    pub async fn handle(self, _global: GlobalArgs) -> Result<()> {
+    pub async fn handle(self, global: GlobalArgs) -> Result<()> {
         info!("Loading profile...");
         let app_profile = Profiles::load_and_get_active_profile()
             .await
@@ -58,13 +65,40 @@ impl SyncCommand {
         );
 
         info!("Reading entries from zips...");
-        let entries = read_entries_from_zips::read_entries_from_zips(zips).await?;
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
         info!(
             "Found {} entries ({}) in the source zips",
             entries.len(),
             entries.human_size()
         );
 
+        let mut unsafe_names = UnsafeNameMapping::load(&app_profile.destination).await?;
+        unsafe_names.extend(&entries);
+        if !unsafe_names.entries.is_empty() {
+            info!(
+                "Recording {} quarantined entry names in {}",
+                unsafe_names.entries.len(),
+                UnsafeNameMapping::path(&app_profile.destination).display()
+            );
+            unsafe_names.save(&app_profile.destination).await?;
+        }
+
+        if app_profile.detect_case_collisions {
+            let collisions = find_case_collisions(
+                entries
+                    .iter()
+                    .map(|entry| &entry.path_inside_zip)
+                    .chain(existing_destination_files.keys()),
+            );
+            for collision in &collisions {
+                warn!(
+                    "Names differ only by case and will collide on a case-insensitive destination: {}",
+                    collision.iter().map(|path| path.display()).join(", ")
+                );
+            }
+        }
+
         let mut not_on_disk: Vec<ZipEntry> = Vec::new();
         for entry in entries {
             if !existing_destination_files.contains_key(&entry.path_inside_zip) {
@@ -82,14 +116,55 @@ impl SyncCommand {
         let (write_to_disk_tx, mut write_to_disk_rx) =
             tokio::sync::mpsc::unbounded_channel::<(ZipEntry, bool)>();
         let write_to_disk_join_handle = tokio::spawn(async move {
-            while let Some((entry, disambiguate)) = write_to_disk_rx.recv().await {
-                let destination = entry.get_splat_path(&app_profile.destination, disambiguate)?;
-                if !destination.exists() {
-                    info!("Writing entry to {}", destination.display());
-                    entry.write_to_file(&destination).await?;
+            let sanitizer = app_profile.name_sanitizer.sanitizer();
+            // Saved once the writer stops, including when a write fails, so a failed sync never leaves a sanitized name unmapped
+            let mut name_mapping = NameMapping::load(&app_profile.destination).await?;
+            let mut name_mapping_changed = false;
+            let written = async {
+                while let Some((entry, disambiguate)) = write_to_disk_rx.recv().await {
+                    let unsanitized =
+                        entry.get_splat_path(&app_profile.destination, disambiguate)?;
+                    let destination = entry.get_sanitized_splat_path(
+                        &app_profile.destination,
+                        disambiguate,
+                        sanitizer,
+                    )?;
+                    if name_mapping.record(
+                        destination
+                            .strip_prefix(&app_profile.destination)?
+                            .to_path_buf(),
+                        unsanitized
+                            .strip_prefix(&app_profile.destination)?
+                            .to_path_buf(),
+                    ) {
+                        debug!(
+                            "Recording sanitized name {} in {}",
+                            destination.display(),
+                            NameMapping::path(&app_profile.destination).display()
+                        );
+                        name_mapping_changed = true;
+                    }
+                    if !destination.exists() {
+                        info!("Writing entry to {}", destination.display());
+                        match original_json_path(&app_profile.destination, &destination) {
+                            Some(original_dest)
+                                if app_profile.fix_json_encoding && entry.is_json() =>
+                            {
+                                entry
+                                    .write_transcoded_json_to_file(&destination, &original_dest)
+                                    .await?;
+                            }
+                            _ => entry.write_to_file(&destination).await?,
+                        }
+                    }
                 }
+                eyre::Ok(())
             }
-            eyre::Ok(())
+            .await;
+            if name_mapping_changed {
+                name_mapping.save(&app_profile.destination).await?;
+            }
+            written
         });
 
         info!("Partitioning entries by name...");
@@ -116,7 +191,7 @@ impl SyncCommand {
             }
         }
         let entries = ambiguous_entries;
-        let hasher_config = Arc::new(HasherConfig::new().hash_alg(HashAlg::Gradient));
+        let hasher_config = Arc::new(image_hasher_config());
 
         let unprocessed = track_progress(
             entries,
@@ -141,65 +216,28 @@ impl SyncCommand {
                     }
 
                     // Check if image hash
-                    let image_extensions: HashSet<_> =
-                        ["jpg", "jpeg", "png", "gif", "bmp", "tiff", "webp", "heic"]
-                            .into_iter()
-                            .map(OsString::from)
-                            .collect();
-                    let is_image = path_inside_zip
-                        .extension()
-                        .map(|ext| image_extensions.contains(ext))
-                        .unwrap_or(false);
-                    if is_image {
-                    let mut images = Vec::with_capacity(entries.len());
-                        let mut failed_to_load_any_images = false;
-                        for entry in entries.iter() {
-                            let data = entry.bytes().await?.to_vec();
-                            let image = load_from_memory(&data);
-                            if let Ok(image) = image {
-                                images.push(image);
-                            } else {
-                                failed_to_load_any_images = true;
-                                break;
-                            }
-                        }
-                        if !failed_to_load_any_images {
-                        let hasher: img_hash::Hasher = hasher_config.to_hasher();
-                            let mut hashes = Vec::with_capacity(images.len());
-                            for image in images {
-                                hashes.push(hasher.hash_image(&image));
-                            }
-                            let mut ambiguous = false;
-                            let mut max_dist = 0;
-                            for i in 0..hashes.len() {
-                                for j in (i + 1)..hashes.len() {
-                                    let d = hashes[i].dist(&hashes[j]);
-                                    max_dist = max_dist.max(d);
-                                    const THRESHOLD: u32 = 5;
-                                    if d > THRESHOLD {
-                                        ambiguous = true;
-                                        break;
-                                    }
-                                }
-                                if ambiguous {
-                                    break;
-                                }
-                            }
-                            info!(
-                                "Images {} have hashes {:?}, max_dist={max_dist}, ambiguous={ambiguous}",
-                                path_inside_zip.display(),
-                                hashes
-                                    .iter()
-                                    .format_with(", ", |h, f| f(&format_args!("{}", h.to_base64())))
-                            );
-                            if !ambiguous {
-                                // make sure we grab the smallest entry by uncompressed size
-                                let zip_entry = entries.into_iter().sorted_by_key(|entry| entry.entry.uncompressed_size).next().unwrap();
-                                    write_to_disk_tx2
-                                        .send((zip_entry, false))
-                                        .expect("Failed to send entry to writer");
-                                return Ok(None);
-                            }
+                    let hashes = if sync_compares_perceptually(&path_inside_zip) {
+                        hash_images(&entries, &hasher_config).await?
+                    } else {
+                        None
+                    };
+                    if let Some(hashes) = hashes {
+                        let max_dist = max_distance(&hashes);
+                        let ambiguous = max_dist > SYNC_SIMILARITY_THRESHOLD;
+                        info!(
+                            "Images {} have hashes {:?}, max_dist={max_dist}, ambiguous={ambiguous}",
+                            path_inside_zip.display(),
+                            hashes
+                                .iter()
+                                .format_with(", ", |h, f| f(&format_args!("{}", h.to_base64())))
+                        );
+                        if !ambiguous {
+                            // make sure we grab the smallest entry by uncompressed size
+                            let zip_entry = entries.into_iter().sorted_by_key(|entry| entry.entry.uncompressed_size).next().unwrap();
+                                write_to_disk_tx2
+                                    .send((zip_entry, false))
+                                    .expect("Failed to send entry to writer");
+                            return Ok(None);
                         }
                     }
 
@@ -238,6 +276,7 @@ impl SyncCommand {
         info!("Waiting for write tasks to complete...");
         write_to_disk_join_handle.await??;
 
+        report_skipped(&skipped);
         Ok(())
     }
 }
@@ -4,7 +4,9 @@ use crate::gather_existing_files::gather_existing_files;
 use crate::get_zips;
 use crate::path_inside_zip::PathInsideZip;
 use crate::progress::worker::track_progress;
+use crate::read_entries::ReadEntries;
 use crate::read_entries_from_zips;
+use crate::skipped_entry::report_skipped;
 use crate::size_of_thing::KnownCount;
 use crate::size_of_thing::KnownSize;
 use crate::state::profiles::Profiles;
@@ -12,13 +14,10 @@ use crate::zip_entry::ZipEntry;
 use clap::Args;
 use color_eyre::eyre::Result;
 use color_eyre::eyre::WrapErr;
-use image::load_from_memory;
 use img_hash::HashAlg;
 use img_hash::HasherConfig;
 use itertools::Itertools;
-use std::collections::HashMap;
 use std::collections::HashSet;
-use std::ffi::OsString;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::info;
@@ -28,7 +27,7 @@ use tracing::warn;
 pub struct ValidateCommand;
 
 impl ValidateCommand {
-    pub async fn handle(self, _global: GlobalArgs) -> Result<()> {
+    pub async fn handle(self, global: GlobalArgs) -> Result<()> {
         info!("Loading profile...");
         let app_profile = Profiles::load_and_get_active_profile()
             .await
@@ -57,7 +56,8 @@ impl ValidateCommand {
         );
 
         info!("Reading entries from zips...");
-        let entries = read_entries_from_zips::read_entries_from_zips(zips).await?;
+        let ReadEntries { entries, skipped } =
+            read_entries_from_zips::read_entries_from_zips(zips, global.recover).await?;
         info!(
             "Found {} entries ({}) in the source zips",
             entries.len(),
@@ -114,6 +114,7 @@ impl ValidateCommand {
         )
         .await?;
 
+        report_skipped(&skipped);
         Ok(())
     }
 }
@@ -125,7 +126,7 @@ pub async fn audit_path(
     path_in_zip: &PathInsideZip,
     existing_files: Vec<ExistingFile>,
     zip_entries: Vec<ZipEntry>,
-    hasher_config: Arc<HasherConfig>,
+    _hasher_config: Arc<HasherConfig>,
 ) -> Result<()> {
     if existing_files.is_empty() && zip_entries.is_empty() {
         warn!("No files found for path {}", path_in_zip.display());
@@ -0,0 +1,48 @@
+use crate::existing_file::ExistingFile;
+use crate::meta::messages::conversation::Conversation;
+use crate::meta::messages::conversation_part::ConversationPart;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+/// Messenger thread parts are named `message_1.json`, `message_2.json` and so on.
+pub fn is_message_file(path_inside_zip: &Path) -> bool {
+    let Some(name) = path_inside_zip.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    name.strip_prefix("message_")
+        .and_then(|rest| rest.strip_suffix(".json"))
+        .is_some_and(|number| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// The message files in the destination grouped by thread.
+///
+/// Disambiguated copies come first, so the canonical part and its title are merged last.
+pub fn message_files(existing_files: &[ExistingFile]) -> HashMap<String, Vec<&ExistingFile>> {
+    let mut rtn = existing_files
+        .iter()
+        .filter(|file| is_message_file(file.path_inside_zip()))
+        .filter_map(|file| Conversation::thread_key(file.path_inside_zip()).map(|key| (key, file)))
+        .into_group_map();
+    for files in rtn.values_mut() {
+        files.sort_by_key(|file| (!file.is_ambiguous(), file.disk_path().clone()));
+    }
+    rtn
+}
+
+/// Parses and merges the parts of one thread, skipping parts that cannot be parsed.
+pub async fn read_conversation(
+    thread_key: String,
+    files: &[&ExistingFile],
+) -> eyre::Result<Conversation> {
+    let mut parts = Vec::with_capacity(files.len());
+    for file in files {
+        let data = tokio::fs::read(file.disk_path()).await?;
+        match ConversationPart::from_json(&data) {
+            Ok(part) => parts.push(part),
+            Err(e) => warn!("Skipping {}: {e:#}", file.disk_path().display()),
+        }
+    }
+    Ok(Conversation::merge(thread_key, parts))
+}
@@ -0,0 +1,78 @@
+use crate::zip_entry::ZipEntry;
+use itertools::Itertools;
+use rand::seq::IteratorRandom;
+use serde::Serialize;
+use tokio::task::JoinSet;
+use tracing::debug;
+use tracing::warn;
+
+/// Outcome of reading entries back and comparing them to the CRC32 stored in the central directory.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CrcVerification {
+    pub checked: usize,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+/// Reads up to `sample_per_zip` random entries from each zip and checks their CRC32.
+pub async fn verify_crc_sample(
+    entries: &[ZipEntry],
+    sample_per_zip: usize,
+) -> eyre::Result<CrcVerification> {
+    let mut tasks: JoinSet<bool> = JoinSet::new();
+    let entries_by_zip = entries
+        .iter()
+        .into_group_map_by(|entry| entry.path_to_zip.clone());
+    for (path_to_zip, zip_entries) in entries_by_zip {
+        let sample = zip_entries
+            .into_iter()
+            .choose_multiple(&mut rand::thread_rng(), sample_per_zip);
+        debug!(
+            "Verifying {} entries from {}",
+            sample.len(),
+            path_to_zip.display()
+        );
+        for entry in sample {
+            let entry = entry.clone();
+            tasks.spawn(async move { verify_crc(&entry).await });
+        }
+    }
+
+    let mut rtn = CrcVerification::default();
+    while let Some(res) = tasks.join_next().await {
+        rtn.checked += 1;
+        if res? {
+            rtn.passed += 1;
+        } else {
+            rtn.failed += 1;
+        }
+    }
+    Ok(rtn)
+}
+
+/// Returns whether the entry reads back successfully with the CRC32 stored in the central directory.
+pub async fn verify_crc(entry: &ZipEntry) -> bool {
+    let data = match entry.bytes().await {
+        Ok(data) => data,
+        Err(e) => {
+            warn!(
+                "Failed to read {} from {}: {e}",
+                entry.path_inside_zip.display(),
+                entry.path_to_zip.display()
+            );
+            return false;
+        }
+    };
+    let actual = crc32fast::hash(&data);
+    if actual != entry.entry.crc32 {
+        warn!(
+            "CRC mismatch for {} in {}: stored={:08x} actual={:08x}",
+            entry.path_inside_zip.display(),
+            entry.path_to_zip.display(),
+            entry.entry.crc32,
+            actual
+        );
+        return false;
+    }
+    true
+}
@@ -0,0 +1,164 @@
+use crate::entry_coverage_record::EntryCoverageRecord;
+use crate::existing_file::ExistingFile;
+use crate::path_inside_zip::PathInsideZip;
+use crate::perceptual::SYNC_SIMILARITY_THRESHOLD;
+use crate::perceptual::hash_image_bytes;
+use crate::perceptual::sync_compares_perceptually;
+use crate::progress::worker::track_progress;
+use crate::sidecar::content_path;
+use crate::zip_entry::ZipEntry;
+use eyre::WrapErr;
+use img_hash::HasherConfig;
+use img_hash::ImageHash;
+use itertools::Itertools;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+/// How a zip entry is represented in the destination.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "coverage", rename_all = "snake_case")]
+pub enum EntryCoverage {
+    /// A destination file has exactly the bytes of the entry
+    ByteIdentical { path_on_disk: PathBuf },
+    /// A destination image is within the similarity threshold sync uses for the entry
+    Perceptual {
+        path_on_disk: PathBuf,
+        distance: u32,
+    },
+    /// Destination files exist for the name, but none of them match the entry
+    Mismatch { candidates: Vec<PathBuf> },
+    /// Nothing in the destination has the entry name
+    Missing,
+}
+impl EntryCoverage {
+    pub fn is_covered(&self) -> bool {
+        matches!(
+            self,
+            EntryCoverage::ByteIdentical { .. } | EntryCoverage::Perceptual { .. }
+        )
+    }
+}
+
+/// Finds the destination file that covers a zip entry.
+///
+/// Candidates are the destination files with the entry name. A disambiguated copy extracted from the same zip is tried first,
+/// and transcoded JSON files are compared using the original bytes kept in the sidecar directory.
+pub async fn check_entry_coverage(
+    entry: &ZipEntry,
+    candidates: &[ExistingFile],
+    destination: &Path,
+    hasher_config: &HasherConfig,
+) -> eyre::Result<EntryCoverage> {
+    if candidates.is_empty() {
+        return Ok(EntryCoverage::Missing);
+    }
+    let zip_file_name = entry
+        .path_to_zip
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    let candidates = candidates
+        .iter()
+        .sorted_by_key(|candidate| match candidate.zip_name() {
+            Some(zip_name) if Some(zip_name) == zip_file_name.as_deref() => 0,
+            None => 1,
+            Some(_) => 2,
+        })
+        .collect_vec();
+
+    for candidate in &candidates {
+        let path_on_disk = candidate.disk_path();
+        let compare_path = content_path(destination, path_on_disk);
+        if tokio::fs::metadata(&compare_path).await?.len() != entry.entry.uncompressed_size {
+            continue;
+        }
+        let data = tokio::fs::read(&compare_path).await?;
+        if crc32fast::hash(&data) == entry.entry.crc32 {
+            return Ok(EntryCoverage::ByteIdentical {
+                path_on_disk: path_on_disk.clone(),
+            });
+        }
+    }
+
+    if sync_compares_perceptually(&entry.path_inside_zip) {
+        let entry_hash: Option<ImageHash> = hash_image_bytes(&entry.bytes().await?, hasher_config);
+        if let Some(entry_hash) = entry_hash {
+            for candidate in &candidates {
+                let data = tokio::fs::read(candidate.disk_path()).await?;
+                let Some(hash) = hash_image_bytes(&data, hasher_config) else {
+                    continue;
+                };
+                let distance = entry_hash.dist(&hash);
+                if distance <= SYNC_SIMILARITY_THRESHOLD {
+                    return Ok(EntryCoverage::Perceptual {
+                        path_on_disk: candidate.disk_path().clone(),
+                        distance,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(EntryCoverage::Mismatch {
+        candidates: candidates
+            .into_iter()
+            .map(|candidate| candidate.disk_path().clone())
+            .collect(),
+    })
+}
+
+/// Checks the coverage of every entry in parallel, sorted by entry name.
+pub async fn check_entries_coverage(
+    zip_entries: Vec<ZipEntry>,
+    existing_destination_files: Arc<HashMap<PathInsideZip, Vec<ExistingFile>>>,
+    destination: PathBuf,
+    hasher_config: Arc<HasherConfig>,
+) -> eyre::Result<Vec<EntryCoverageRecord>> {
+    let records = track_progress(
+        zip_entries,
+        Duration::from_millis(500),
+        |progress| info!("Enqueueing {progress}"),
+        |progress| info!("Checking {progress}"),
+        |_progress, elapsed| info!("Checked in {elapsed}"),
+        move |entry: ZipEntry| {
+            let existing_destination_files = existing_destination_files.clone();
+            let destination = destination.clone();
+            let hasher_config = hasher_config.clone();
+            async move {
+                let candidates = existing_destination_files
+                    .get(&entry.path_inside_zip)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let coverage =
+                    check_entry_coverage(&entry, candidates, &destination, &hasher_config)
+                        .await
+                        .wrap_err_with(|| {
+                            format!(
+                                "Failed to check coverage of {}",
+                                entry.path_inside_zip.display()
+                            )
+                        })?;
+                Ok(EntryCoverageRecord {
+                    entry_name: entry.entry.name.clone(),
+                    path_inside_zip: entry.path_inside_zip.to_path_buf(),
+                    crc32: entry.entry.crc32,
+                    uncompressed_size: entry.entry.uncompressed_size,
+                    coverage,
+                })
+            }
+        },
+        24,
+    )
+    .await?;
+    Ok(records
+        .into_iter()
+        .sorted_by(|a, b| {
+            (&a.path_inside_zip, &a.entry_name).cmp(&(&b.path_inside_zip, &b.entry_name))
+        })
+        .collect())
+}
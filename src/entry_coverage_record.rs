@@ -0,0 +1,17 @@
+use crate::entry_coverage::EntryCoverage;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// One line of a retire report.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EntryCoverageRecord {
+    /// The name as stored in the zip, before sanitizing
+    #[serde(default)]
+    pub entry_name: String,
+    pub path_inside_zip: PathBuf,
+    pub crc32: u32,
+    pub uncompressed_size: u64,
+    #[serde(flatten)]
+    pub coverage: EntryCoverage,
+}
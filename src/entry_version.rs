@@ -0,0 +1,20 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// One zip's copy of an entry name, as listed by the history command.
+#[derive(Serialize, Debug, Clone)]
+pub struct EntryVersion {
+    pub zip: PathBuf,
+    pub export_date: NaiveDate,
+    pub crc32: u32,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub method: String,
+    /// Base64 perceptual hash, for images that could be decoded
+    pub perceptual_hash: Option<String>,
+    /// Whether sync wrote this exact variant to the destination
+    pub chosen: bool,
+    /// The destination file holding this variant, or the perceptually equivalent variant that replaced it
+    pub destination_file: Option<PathBuf>,
+}
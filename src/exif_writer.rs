@@ -0,0 +1,208 @@
+use chrono::NaiveDateTime;
+
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_GPS_VERSION_ID: u16 = 0x0000;
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+const TYPE_BYTE: u16 = 1;
+const TYPE_ASCII: u16 = 2;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// Builds a JPEG APP1 segment holding `DateTimeOriginal` and, when given, a GPS position.
+///
+/// Only the handful of tags Meta strips are written, so a tiny hand-rolled little-endian TIFF structure is enough.
+pub fn build_exif_app1(taken: NaiveDateTime, gps: Option<(f64, f64)>) -> Vec<u8> {
+    let ifd_len = |entries: u32| 2 + 12 * entries + 4;
+    let ifd0_entries = if gps.is_some() { 2 } else { 1 };
+    let exif_ifd = 8 + ifd_len(ifd0_entries);
+    let date_data = exif_ifd + ifd_len(1);
+    let gps_ifd = date_data + 20;
+    let latitude_data = gps_ifd + ifd_len(5);
+    let longitude_data = latitude_data + 24;
+
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II*\0");
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+
+    write_ifd_header(&mut tiff, ifd0_entries);
+    write_entry(
+        &mut tiff,
+        TAG_EXIF_IFD,
+        TYPE_LONG,
+        1,
+        exif_ifd.to_le_bytes(),
+    );
+    if gps.is_some() {
+        write_entry(&mut tiff, TAG_GPS_IFD, TYPE_LONG, 1, gps_ifd.to_le_bytes());
+    }
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+
+    write_ifd_header(&mut tiff, 1);
+    write_entry(
+        &mut tiff,
+        TAG_DATE_TIME_ORIGINAL,
+        TYPE_ASCII,
+        20,
+        date_data.to_le_bytes(),
+    );
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    tiff.extend_from_slice(taken.format("%Y:%m:%d %H:%M:%S").to_string().as_bytes());
+    tiff.push(0);
+
+    if let Some((latitude, longitude)) = gps {
+        write_ifd_header(&mut tiff, 5);
+        write_entry(&mut tiff, TAG_GPS_VERSION_ID, TYPE_BYTE, 4, [2, 3, 0, 0]);
+        let latitude_ref = if latitude < 0.0 { b'S' } else { b'N' };
+        write_entry(
+            &mut tiff,
+            TAG_GPS_LATITUDE_REF,
+            TYPE_ASCII,
+            2,
+            [latitude_ref, 0, 0, 0],
+        );
+        write_entry(
+            &mut tiff,
+            TAG_GPS_LATITUDE,
+            TYPE_RATIONAL,
+            3,
+            latitude_data.to_le_bytes(),
+        );
+        let longitude_ref = if longitude < 0.0 { b'W' } else { b'E' };
+        write_entry(
+            &mut tiff,
+            TAG_GPS_LONGITUDE_REF,
+            TYPE_ASCII,
+            2,
+            [longitude_ref, 0, 0, 0],
+        );
+        write_entry(
+            &mut tiff,
+            TAG_GPS_LONGITUDE,
+            TYPE_RATIONAL,
+            3,
+            longitude_data.to_le_bytes(),
+        );
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        write_degrees(&mut tiff, latitude);
+        write_degrees(&mut tiff, longitude);
+    }
+
+    let mut app1 = vec![0xFF, 0xE1];
+    let len = (2 + EXIF_HEADER.len() + tiff.len()) as u16;
+    app1.extend_from_slice(&len.to_be_bytes());
+    app1.extend_from_slice(EXIF_HEADER);
+    app1.extend_from_slice(&tiff);
+    app1
+}
+
+/// Inserts an APP1 segment into a JPEG, after the JFIF header when there is one.
+/// Returns `None` if the data is not a JPEG or already carries EXIF data.
+pub fn insert_exif(jpeg: &[u8], app1: &[u8]) -> Option<Vec<u8>> {
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut insert_at = 2;
+    let mut pos = 2;
+    // Application segments come first, so only those need to be walked
+    while pos + 4 <= jpeg.len() && jpeg[pos] == 0xFF && (0xE0..=0xEF).contains(&jpeg[pos + 1]) {
+        let marker = jpeg[pos + 1];
+        let len = u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+        if marker == 0xE1 && jpeg[pos + 4..].starts_with(EXIF_HEADER) {
+            return None;
+        }
+        if marker == 0xE0 && pos == 2 {
+            insert_at = pos + 2 + len;
+        }
+        pos += 2 + len;
+    }
+    let insert_at = insert_at.min(jpeg.len());
+    let mut rtn = Vec::with_capacity(jpeg.len() + app1.len());
+    rtn.extend_from_slice(&jpeg[..insert_at]);
+    rtn.extend_from_slice(app1);
+    rtn.extend_from_slice(&jpeg[insert_at..]);
+    Some(rtn)
+}
+
+fn write_ifd_header(tiff: &mut Vec<u8>, entries: u32) {
+    tiff.extend_from_slice(&(entries as u16).to_le_bytes());
+}
+
+fn write_entry(tiff: &mut Vec<u8>, tag: u16, kind: u16, count: u32, value: [u8; 4]) {
+    tiff.extend_from_slice(&tag.to_le_bytes());
+    tiff.extend_from_slice(&kind.to_le_bytes());
+    tiff.extend_from_slice(&count.to_le_bytes());
+    tiff.extend_from_slice(&value);
+}
+
+/// Writes an absolute coordinate as degrees, minutes and hundredths of seconds.
+fn write_degrees(tiff: &mut Vec<u8>, coordinate: f64) {
+    let coordinate = coordinate.abs();
+    let degrees = coordinate.trunc();
+    let minutes = ((coordinate - degrees) * 60.0).trunc();
+    let seconds = ((coordinate - degrees) * 60.0 - minutes) * 60.0;
+    for (numerator, denominator) in [
+        (degrees as u32, 1u32),
+        (minutes as u32, 1),
+        ((seconds * 100.0).round() as u32, 100),
+    ] {
+        tiff.extend_from_slice(&numerator.to_le_bytes());
+        tiff.extend_from_slice(&denominator.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::build_exif_app1;
+    use super::insert_exif;
+    use chrono::NaiveDate;
+
+    fn taken() -> chrono::NaiveDateTime {
+        NaiveDate::from_ymd_opt(2017, 7, 14)
+            .unwrap()
+            .and_hms_opt(2, 40, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn builds_date_segment() {
+        let app1 = build_exif_app1(taken(), None);
+        assert_eq!(&app1[..2], &[0xFF, 0xE1]);
+        assert_eq!(
+            u16::from_be_bytes([app1[2], app1[3]]) as usize,
+            app1.len() - 2
+        );
+        assert_eq!(&app1[4..14], b"Exif\0\0II*\0");
+        let date = b"2017:07:14 02:40:00\0";
+        assert!(app1.windows(date.len()).any(|window| window == date));
+    }
+
+    #[test]
+    fn builds_gps_segment() {
+        let without = build_exif_app1(taken(), None);
+        let with = build_exif_app1(taken(), Some((-33.8568, 151.2153)));
+        // One more IFD0 entry, a five entry GPS IFD and two coordinates
+        assert_eq!(with.len() - without.len(), 12 + (2 + 12 * 5 + 4) + 48);
+        assert!(with.windows(2).any(|window| window == b"S\0"));
+        assert!(with.windows(2).any(|window| window == b"E\0"));
+    }
+
+    #[test]
+    fn inserts_after_jfif_once() {
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
+        jpeg.extend_from_slice(b"JFIF\0");
+        jpeg.extend_from_slice(&[0; 9]);
+        jpeg.extend_from_slice(&[0xFF, 0xDB, 0x00, 0x02, 0xFF, 0xD9]);
+        let app1 = build_exif_app1(taken(), None);
+        let with_exif = insert_exif(&jpeg, &app1).unwrap();
+        assert_eq!(&with_exif[20..22], &[0xFF, 0xE1]);
+        assert_eq!(with_exif.len(), jpeg.len() + app1.len());
+        assert_eq!(insert_exif(&with_exif, &app1), None);
+        assert_eq!(insert_exif(b"not a jpeg", &app1), None);
+    }
+}
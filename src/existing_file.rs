@@ -58,6 +58,22 @@ impl ExistingFile {
         }
     }
 
+    /// Where the file was found on disk, for either variant.
+    pub fn disk_path(&self) -> &PathBuf {
+        match self {
+            ExistingFile::Unambiguous { path_on_disk, .. } => path_on_disk,
+            ExistingFile::Ambiguous { path_on_disk, .. } => path_on_disk,
+        }
+    }
+
+    /// Name of the zip a disambiguated file was extracted from.
+    pub fn zip_name(&self) -> Option<&str> {
+        match self {
+            ExistingFile::Unambiguous { .. } => None,
+            ExistingFile::Ambiguous { zip_name, .. } => Some(zip_name),
+        }
+    }
+
     pub fn is_ambiguous(&self) -> bool {
         matches!(self, ExistingFile::Ambiguous { .. })
     }
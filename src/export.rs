@@ -0,0 +1,49 @@
+use crate::export_date::get_export_date;
+use crate::path_to_zip::PathToZip;
+use chrono::NaiveDate;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// All the zips Meta produced for one export, which is usually split into several parts.
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub date: NaiveDate,
+    pub zips: Vec<PathToZip>,
+}
+
+/// Returns the export date of every zip.
+pub async fn get_export_dates(zips: &[PathToZip]) -> eyre::Result<HashMap<PathToZip, NaiveDate>> {
+    let mut rtn = HashMap::with_capacity(zips.len());
+    for zip in zips {
+        rtn.insert(zip.clone(), get_export_date(zip).await?);
+    }
+    Ok(rtn)
+}
+
+/// Groups zips by export date, oldest export first.
+pub async fn group_zips_by_export(zips: &[PathToZip]) -> eyre::Result<Vec<Export>> {
+    let dates = get_export_dates(zips).await?;
+    Ok(zips
+        .iter()
+        .cloned()
+        .into_group_map_by(|zip| dates[zip])
+        .into_iter()
+        .map(|(date, zips)| Export {
+            date,
+            zips: zips
+                .into_iter()
+                .sorted_by_key(|zip| zip.to_path_buf())
+                .collect(),
+        })
+        .sorted_by_key(|export| export.date)
+        .collect())
+}
+
+/// Returns the position in `exports` of the export each zip belongs to.
+pub fn export_index(exports: &[Export]) -> HashMap<PathToZip, usize> {
+    exports
+        .iter()
+        .enumerate()
+        .flat_map(|(index, export)| export.zips.iter().map(move |zip| (zip.clone(), index)))
+        .collect()
+}
@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+/// How an entry name differs between two exports.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportChange {
+    Added,
+    Removed,
+    Changed,
+}
+impl ExportChange {
+    /// Prefix used in the table output, like a unified diff.
+    pub fn symbol(&self) -> char {
+        match self {
+            ExportChange::Added => '+',
+            ExportChange::Removed => '-',
+            ExportChange::Changed => '~',
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::path_to_zip::PathToZip;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDate;
+
+/// Finds the last `YYYY-MM-DD` date in a zip file name, such as `facebook-user-2024-06-19-228yS1FQ.zip`.
+pub fn export_date_from_name(name: &str) -> Option<NaiveDate> {
+    let bytes = name.as_bytes();
+    (0..bytes.len().saturating_sub(9))
+        .rev()
+        .filter_map(|start| name.get(start..start + 10))
+        .find_map(|candidate| NaiveDate::parse_from_str(candidate, "%Y-%m-%d").ok())
+}
+
+/// Returns the date a zip was exported, taken from its file name or else its modified time.
+pub async fn get_export_date(path_to_zip: &PathToZip) -> eyre::Result<NaiveDate> {
+    if let Some(date) = path_to_zip
+        .file_name()
+        .and_then(|name| export_date_from_name(&name.to_string_lossy()))
+    {
+        return Ok(date);
+    }
+    let modified = tokio::fs::metadata(path_to_zip).await?.modified()?;
+    Ok(DateTime::<Local>::from(modified).date_naive())
+}
+
+#[cfg(test)]
+mod test {
+    use super::export_date_from_name;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn parses_export_names() {
+        assert_eq!(
+            export_date_from_name("facebook-Dominic9201-2024-06-19-228yS1FQ.zip"),
+            NaiveDate::from_ymd_opt(2024, 6, 19)
+        );
+        assert_eq!(
+            export_date_from_name("2025-06-17.zip"),
+            NaiveDate::from_ymd_opt(2025, 6, 17)
+        );
+        assert_eq!(export_date_from_name("backup.zip"), None);
+        assert_eq!(
+            export_date_from_name("ü-2025-06-17"),
+            NaiveDate::from_ymd_opt(2025, 6, 17)
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use crate::export_change::ExportChange;
+use crate::meta::record_diff::RecordDiff;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// One entry name that was added, removed or changed between two exports.
+#[derive(Serialize, Debug, Clone)]
+pub struct ExportDiffEntry {
+    pub change: ExportChange,
+    pub path_inside_zip: PathBuf,
+    /// Uncompressed size in the old export
+    pub old_size: Option<u64>,
+    /// Uncompressed size in the new export
+    pub new_size: Option<u64>,
+    /// Perceptual hash distance, for changed images that could be decoded
+    pub perceptual_distance: Option<u32>,
+    /// Record level summary, for changed JSON files that could be parsed
+    pub records: Option<RecordDiff>,
+}
+impl ExportDiffEntry {
+    pub fn size_delta(&self) -> i64 {
+        self.new_size.unwrap_or_default() as i64 - self.old_size.unwrap_or_default() as i64
+    }
+}
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+/// How the entries with one file extension vary across exports.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ExtensionStats {
+    pub extension: String,
+    /// Number of unique entry names
+    pub count: usize,
+    /// Pairs of variants of the same name with equal CRC32
+    pub crc_matches: usize,
+    /// Pairs of variants of the same name with different CRC32
+    pub crc_mismatches: usize,
+    /// Variants with a zero CRC32, which are excluded from the comparisons
+    pub crc_zeros: usize,
+    /// Pairs where the variant from the newer export is larger
+    pub newer_larger: usize,
+    /// Pairs where the variant from the newer export is smaller
+    pub newer_smaller: usize,
+    /// Pairs from different exports with equal size
+    pub newer_equal: usize,
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+
+/// How a person's presence in a list changes across exports ordered by date.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FriendChange {
+    /// Missing from the oldest export, present from some export up to the newest
+    Added,
+    /// Present in the oldest export, missing from some export up to the newest
+    Removed,
+    /// Missing from both the oldest and the newest export, present only in between
+    Transient,
+    /// Disappears and comes back again
+    Intermittent,
+    /// Present in every export
+    Unchanged,
+}
+impl FriendChange {
+    /// Classifies presence flags given oldest export first. Returns `None` if the person is in no export.
+    pub fn classify(presence: &[bool]) -> Option<Self> {
+        let first = presence.iter().position(|present| *present)?;
+        let last = presence.iter().rposition(|present| *present)?;
+        if presence[first..=last].iter().any(|present| !present) {
+            return Some(FriendChange::Intermittent);
+        }
+        let in_oldest = first == 0;
+        let in_newest = last == presence.len() - 1;
+        Some(match (in_oldest, in_newest) {
+            (true, true) => FriendChange::Unchanged,
+            (false, true) => FriendChange::Added,
+            (true, false) => FriendChange::Removed,
+            (false, false) => FriendChange::Transient,
+        })
+    }
+
+    /// Prefix used in the table output, like a unified diff.
+    pub fn symbol(&self) -> char {
+        match self {
+            FriendChange::Added => '+',
+            FriendChange::Removed => '-',
+            FriendChange::Transient => '~',
+            FriendChange::Intermittent => '?',
+            FriendChange::Unchanged => '=',
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FriendChange;
+
+    #[test]
+    fn classifies_presence() {
+        assert_eq!(FriendChange::classify(&[false, false]), None);
+        assert_eq!(
+            FriendChange::classify(&[true, true, true]),
+            Some(FriendChange::Unchanged)
+        );
+        assert_eq!(
+            FriendChange::classify(&[false, true, true]),
+            Some(FriendChange::Added)
+        );
+        assert_eq!(
+            FriendChange::classify(&[true, true, false]),
+            Some(FriendChange::Removed)
+        );
+        assert_eq!(
+            FriendChange::classify(&[false, true, false]),
+            Some(FriendChange::Transient)
+        );
+        assert_eq!(
+            FriendChange::classify(&[true, false, true]),
+            Some(FriendChange::Intermittent)
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use crate::friend_change::FriendChange;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// One person in one list, as seen across every export.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FriendRecord {
+    /// The file the person was listed in, such as `friends` or `removed_friends`
+    pub list: String,
+    pub name: String,
+    pub change: FriendChange,
+    /// Date of the oldest export listing the person
+    pub first_seen: NaiveDate,
+    /// Date of the newest export listing the person
+    pub last_seen: NaiveDate,
+    /// Dates of exports between the first and last sighting that do not list the person
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_from: Vec<NaiveDate>,
+    /// When Meta says the connection was made, from the newest export that lists it
+    pub timestamp: Option<i64>,
+}
@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+
+/// How gallery entries point at the files in the destination.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GalleryLinkKind {
+    /// Hard links, which need the gallery on the same volume as the destination
+    #[default]
+    Hardlink,
+    /// Symbolic links, which on Windows need developer mode or administrator rights
+    Symlink,
+}
@@ -1,11 +1,14 @@
 use crate::existing_file::ExistingFile;
+use crate::name_mapping::NameMapping;
 use crate::path_inside_zip::PathInsideZip;
+use crate::sidecar::SIDECAR_DIR_NAME;
 use std::path::Path;
 use std::sync::Arc;
 use uom::si::f64::Information;
 use uom::si::information::byte;
 
 pub async fn gather_existing_files(dir: &Path) -> eyre::Result<Vec<ExistingFile>> {
+    let name_mapping = NameMapping::load(dir).await?;
     let mut files = Vec::new();
     let mut stack = vec![dir.to_path_buf()];
     while let Some(d) = stack.pop() {
@@ -18,10 +21,16 @@ pub async fn gather_existing_files(dir: &Path) -> eyre::Result<Vec<ExistingFile>
             let metadata = tokio::fs::metadata(&existing_file_path).await?;
             let size = Information::new::<byte>(metadata.len() as f64);
             if metadata.is_dir() {
+                if d == dir && existing_file_dir_entry.file_name() == SIDECAR_DIR_NAME {
+                    continue; // Skip thrumzip bookkeeping files
+                }
                 stack.push(existing_file_path);
             } else {
+                // Map sanitized names back to the names used inside the zips
+                let logical_path =
+                    dir.join(name_mapping.original(existing_file_path.strip_prefix(dir)?));
                 // Determine if parent dir ends with .zip
-                if let Some(parent_dir_named_zip) = existing_file_path.parent().filter(|parent| {
+                if let Some(parent_dir_named_zip) = logical_path.parent().filter(|parent| {
                     parent
                         .file_name()
                         .is_some_and(|name| name.to_string_lossy().ends_with(".zip"))
@@ -31,7 +40,7 @@ pub async fn gather_existing_files(dir: &Path) -> eyre::Result<Vec<ExistingFile>
                             parent_dir_named_zip
                                 .parent()
                                 .unwrap()
-                                .join(existing_file_path.file_name().unwrap())
+                                .join(logical_path.file_name().unwrap())
                                 .strip_prefix(dir)
                                 .unwrap()
                                 .to_path_buf(),
@@ -50,7 +59,7 @@ pub async fn gather_existing_files(dir: &Path) -> eyre::Result<Vec<ExistingFile>
                 } else {
                     files.push(ExistingFile::Unambiguous {
                         path_inside_zip: PathInsideZip::from(Arc::new(
-                            existing_file_path.strip_prefix(dir).unwrap().to_path_buf(),
+                            logical_path.strip_prefix(dir).unwrap().to_path_buf(),
                         )),
                         path_on_disk: existing_file_path.clone(),
                         size,
@@ -120,7 +129,9 @@ mod test {
         );
 
         info!("Reading entries from zips...");
-        let entries = read_entries_from_zips::read_entries_from_zips(zips).await?;
+        let entries = read_entries_from_zips::read_entries_from_zips(zips, false)
+            .await?
+            .entries;
         info!(
             "Found {} entries ({}) in the source zips",
             entries.len(),
@@ -155,7 +166,7 @@ mod test {
             })
             .ok_or_eyre("No feed.json found in source zips")?;
         assert!(
-            feed_from_dest.len() > 0,
+            !feed_from_dest.is_empty(),
             "Expected at least one feed.json in destination directory"
         );
         assert_eq!(
@@ -1,3 +1,4 @@
+use crate::name_sanitizer::NameSanitizer;
 use crate::path_inside_zip::PathInsideZip;
 use crate::path_to_zip::PathToZip;
 use eyre::OptionExt;
@@ -36,9 +37,25 @@ pub fn get_splat_path(
     Ok(dest_dir.join(splatted))
 }
 
+/// Like [`get_splat_path`], but passes everything below `dest_dir` through a [`NameSanitizer`]
+/// so the result can be created on the destination filesystem.
+pub fn get_sanitized_splat_path(
+    path_inside_zip: &PathInsideZip,
+    path_to_zip: &PathToZip,
+    dest_dir: &Path,
+    disambiguate: bool,
+    sanitizer: &dyn NameSanitizer,
+) -> eyre::Result<PathBuf> {
+    let splat_path = get_splat_path(path_inside_zip, path_to_zip, dest_dir, disambiguate)?;
+    let relative = splat_path.strip_prefix(dest_dir)?;
+    Ok(dest_dir.join(sanitizer.sanitize_path(relative)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::noop_name_sanitizer::NoopNameSanitizer;
+    use crate::windows_name_sanitizer::WindowsNameSanitizer;
     use std::sync::Arc;
 
     fn create_path_inside_zip(path: &str) -> PathInsideZip {
@@ -179,6 +196,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_sanitized_splat_path() {
+        let path_inside_zip = create_path_inside_zip("d/what?/CON.txt");
+        let path_to_zip = create_path_to_zip("/a/b/c.zip");
+        let dest_dir = Path::new("/dest");
+
+        let result = get_sanitized_splat_path(
+            &path_inside_zip,
+            &path_to_zip,
+            dest_dir,
+            true,
+            &WindowsNameSanitizer,
+        )
+        .unwrap();
+        assert_eq!(result, PathBuf::from("/dest/d/what%3F/c.zip/%43ON.txt"));
+
+        let result = get_sanitized_splat_path(
+            &path_inside_zip,
+            &path_to_zip,
+            dest_dir,
+            false,
+            &NoopNameSanitizer,
+        )
+        .unwrap();
+        assert_eq!(result, PathBuf::from("/dest/d/what?/CON.txt"));
+    }
+
     #[test]
     fn test_get_splat_path_unicode_characters() {
         let path_inside_zip = create_path_inside_zip("フォルダ/ファイル.txt");
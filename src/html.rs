@@ -0,0 +1,117 @@
+use std::fmt::Write;
+use std::path::Component;
+use std::path::Path;
+use std::path::Prefix;
+
+/// Escapes text for use in HTML element content and quoted attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A relative URL from a page in `from_dir` to `target`, so the site keeps working when it is moved along with the destination.
+///
+/// Both paths should be absolute. Paths on different drives have no relative form and become `file://` URLs.
+pub fn relative_href(from_dir: &Path, target: &Path) -> String {
+    let from = from_dir.components().collect::<Vec<_>>();
+    let to = target.components().collect::<Vec<_>>();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        let mut url = "file://".to_string();
+        for component in &to {
+            match component {
+                Component::Prefix(prefix) => match prefix.kind() {
+                    Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+                        _ = write!(url, "/{}:", letter as char);
+                    }
+                    Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+                        _ = write!(
+                            url,
+                            "{}/{}",
+                            server.to_string_lossy(),
+                            encode_component(&share.to_string_lossy())
+                        );
+                    }
+                    _ => {}
+                },
+                Component::Normal(name) => {
+                    url.push('/');
+                    url.push_str(&encode_component(&name.to_string_lossy()));
+                }
+                _ => {}
+            }
+        }
+        return url;
+    }
+    let ups = from[common..].iter().map(|_| "..".to_string());
+    let downs = to[common..]
+        .iter()
+        .map(|component| encode_component(&component.as_os_str().to_string_lossy()));
+    ups.chain(downs).collect::<Vec<_>>().join("/")
+}
+
+/// Percent-encodes everything in a path component except unreserved URL characters.
+fn encode_component(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::escape_html;
+    use super::relative_href;
+    use std::path::Path;
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(
+            escape_html(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn builds_relative_hrefs() {
+        assert_eq!(
+            relative_href(
+                Path::new("/data/meta_site/threads"),
+                Path::new("/data/meta/messages/inbox/bob_1/photos/a b.jpg")
+            ),
+            "../../meta/messages/inbox/bob_1/photos/a%20b.jpg"
+        );
+        assert_eq!(
+            relative_href(Path::new("/site"), Path::new("/site/thumbs/1.jpg")),
+            "thumbs/1.jpg"
+        );
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn links_to_other_drives_by_file_url() {
+        assert_eq!(
+            relative_href(Path::new(r"C:\site"), Path::new(r"D:\meta\photos\a b.jpg")),
+            "file:///D:/meta/photos/a%20b.jpg"
+        );
+        assert_eq!(
+            relative_href(Path::new(r"C:\site"), Path::new(r"\\nas\backup\meta\a.jpg")),
+            "file://nas/backup/meta/a.jpg"
+        );
+    }
+}
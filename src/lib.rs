@@ -1,15 +1,77 @@
 #![allow(async_fn_in_trait)]
+pub mod assumption_offender;
+pub mod assumption_result;
+pub mod attachment_index;
+pub mod canonical_variant;
+pub mod case_collisions;
 pub mod command;
+pub mod content_line;
+pub mod conversation_files;
+pub mod crc_verification;
+pub mod entry_coverage;
+pub mod entry_coverage_record;
+pub mod entry_version;
+pub mod exif_writer;
 pub mod existing_file;
+pub mod export;
+pub mod export_change;
+pub mod export_date;
+pub mod export_diff_entry;
+pub mod extension_stats;
+pub mod friend_change;
+pub mod friend_record;
+pub mod gallery_link_kind;
 pub mod gather_existing_files;
 pub mod get_splat_path;
 pub mod get_zips;
+pub mod html;
 pub mod init_tracing;
+pub mod link_issue;
+pub mod link_status;
+pub mod lost_record;
+pub mod mail;
+pub mod mail_attachment;
+pub mod mail_format;
+pub mod media_files;
+pub mod media_metadata_index;
+pub mod merged_contact;
+pub mod merged_event;
+pub mod meta;
 pub mod metrics;
+pub mod name_mapping;
+pub mod name_sanitizer;
+pub mod name_sanitizer_kind;
+pub mod noop_name_sanitizer;
+pub mod output_format;
 pub mod path_inside_zip;
 pub mod path_to_zip;
+pub mod perceptual;
 pub mod progress;
+pub mod provenance;
+pub mod provenance_record;
+pub mod read_entries;
 pub mod read_entries_from_zips;
+pub mod rebuild_issue;
+pub mod rebuild_report;
+pub mod record_loss;
+pub mod recover_entries;
+pub mod render_site;
+pub mod render_state;
+pub mod rendered_page;
+pub mod retire_report;
+pub mod savings_report;
+pub mod schema_drift;
+pub mod search_hit;
+pub mod search_index;
+pub mod search_query;
+pub mod select_zips;
+pub mod sidecar;
 pub mod size_of_thing;
+pub mod skipped_entry;
 pub mod state;
+pub mod unsafe_names;
+pub mod variant_mode;
+pub mod windows_name_sanitizer;
+pub mod zip_contribution;
 pub mod zip_entry;
+pub mod zip_fingerprint;
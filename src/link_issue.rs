@@ -0,0 +1,12 @@
+use crate::link_status::LinkStatus;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A `uri` that does not resolve to a file at the path it names.
+#[derive(Serialize, Debug, Clone)]
+pub struct LinkIssue {
+    pub json_file: PathBuf,
+    pub uri: String,
+    #[serde(flatten)]
+    pub status: LinkStatus,
+}
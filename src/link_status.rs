@@ -0,0 +1,14 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// What a `uri` in a synced JSON file points at in the destination.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// The file was only written with disambiguation, under a `<zip>.zip/` folder
+    Disambiguated { target: String },
+    /// Several disambiguated copies exist and none came from the same zip as the JSON file
+    Ambiguous { candidates: Vec<PathBuf> },
+    /// Nothing in the destination has the referenced path
+    Dangling,
+}
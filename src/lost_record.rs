@@ -0,0 +1,21 @@
+use crate::meta::record_kind::RecordKind;
+use crate::record_loss::RecordLoss;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A record that older exports have and the newest export does not.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LostRecord {
+    pub kind: RecordKind,
+    pub loss: RecordLoss,
+    /// The file, or for messages the thread folder, the record was found in
+    pub path_inside_zip: PathBuf,
+    pub summary: String,
+    /// The record's own timestamp, in seconds since the epoch
+    pub timestamp: Option<i64>,
+    /// Date of the newest export that still had the record
+    pub last_seen: NaiveDate,
+    /// The zip of that export the record was read from
+    pub last_seen_zip: PathBuf,
+}
@@ -0,0 +1,315 @@
+use crate::mail_attachment::MailAttachment;
+use crate::meta::messages::conversation::Conversation;
+use crate::meta::messages::message::Message;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::DateTime;
+use std::fmt::Write;
+
+/// Domain of the made up addresses given to Messenger participants, reserved so it never reaches a real mailbox.
+const ADDRESS_DOMAIN: &str = "messenger.invalid";
+
+/// Longest base64 line in a MIME body.
+const BASE64_LINE_LEN: usize = 76;
+
+/// Longest run of UTF-8 bytes put in one RFC 2047 encoded word, keeping the word under 75 characters.
+const ENCODED_WORD_BYTES: usize = 45;
+
+/// Builds an RFC 5322 message with CRLF line endings for one Messenger message.
+///
+/// Attachments become base64 MIME parts; attachments that were not found in the destination are listed in the body.
+pub fn build_mail(
+    conversation: &Conversation,
+    message: &Message,
+    attachments: &[MailAttachment],
+    missing: &[String],
+) -> String {
+    let sender = &message.sender_name;
+    let recipients = conversation
+        .participants
+        .iter()
+        .filter(|participant| participant.name != *sender)
+        .map(|participant| mailbox(&participant.name))
+        .collect::<Vec<_>>();
+    let date = DateTime::from_timestamp_millis(message.timestamp_ms).unwrap_or_default();
+    let subject = conversation
+        .title
+        .as_deref()
+        .unwrap_or(&conversation.thread_key);
+
+    let mut mail = String::new();
+    _ = write!(mail, "From: {}\r\n", mailbox(sender));
+    if recipients.is_empty() {
+        _ = write!(mail, "To: {}\r\n", mailbox(sender));
+    } else {
+        _ = write!(mail, "To: {}\r\n", recipients.join(",\r\n "));
+    }
+    _ = write!(mail, "Date: {}\r\n", date.to_rfc2822());
+    _ = write!(mail, "Subject: {}\r\n", encode_header(subject));
+    _ = write!(
+        mail,
+        "Message-ID: <{}.{:08x}.{}@{}.{ADDRESS_DOMAIN}>\r\n",
+        message.timestamp_ms,
+        message.content_hash(),
+        local_part(sender),
+        local_part(&conversation.thread_key)
+    );
+    mail.push_str("MIME-Version: 1.0\r\n");
+
+    let text = crlf(&body_text(message, missing));
+    if attachments.is_empty() {
+        mail.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+        mail.push_str("Content-Transfer-Encoding: 8bit\r\n\r\n");
+        mail.push_str(&text);
+        return mail;
+    }
+
+    // `=_` cannot occur in base64, so the boundary never collides with attachment data
+    let boundary = format!(
+        "=_thrumzip_{}_{:08x}",
+        message.timestamp_ms,
+        message.content_hash()
+    );
+    _ = write!(
+        mail,
+        "Content-Type: multipart/mixed; boundary=\"{boundary}\"\r\n\r\n"
+    );
+    _ = write!(mail, "--{boundary}\r\n");
+    mail.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+    mail.push_str("Content-Transfer-Encoding: 8bit\r\n\r\n");
+    mail.push_str(&text);
+    for attachment in attachments {
+        let file_name = encode_header(&attachment.file_name).replace('"', "");
+        _ = write!(mail, "--{boundary}\r\n");
+        _ = write!(
+            mail,
+            "Content-Type: {}; name=\"{file_name}\"\r\n",
+            attachment.content_type
+        );
+        _ = write!(
+            mail,
+            "Content-Disposition: attachment; filename=\"{file_name}\"\r\n"
+        );
+        mail.push_str("Content-Transfer-Encoding: base64\r\n\r\n");
+        mail.push_str(&base64_lines(&attachment.data));
+    }
+    _ = write!(mail, "--{boundary}--\r\n");
+    mail
+}
+
+/// Wraps a message for an mbox file: a `From ` separator line, `>From ` quoting in the mboxrd style, and LF line endings.
+pub fn mbox_entry(mail: &str, message: &Message) -> String {
+    let date = DateTime::from_timestamp_millis(message.timestamp_ms).unwrap_or_default();
+    let mut entry = format!(
+        "From {} {}\n",
+        address(&message.sender_name),
+        date.format("%a %b %e %H:%M:%S %Y")
+    );
+    for line in mail.split("\r\n") {
+        if line.trim_start_matches('>').starts_with("From ") {
+            entry.push('>');
+        }
+        entry.push_str(line);
+        entry.push('\n');
+    }
+    entry.push('\n');
+    entry
+}
+
+/// MIME type of an attachment, guessed from its extension.
+pub fn content_type(file_name: &str) -> &'static str {
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "heic" => "image/heic",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "3gp" => "video/3gpp",
+        "mp3" => "audio/mpeg",
+        "m4a" | "aac" => "audio/mp4",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A stable made up address for a participant, such as `bob.smith@messenger.invalid`.
+pub fn address(name: &str) -> String {
+    format!("{}@{ADDRESS_DOMAIN}", local_part(name))
+}
+
+fn local_part(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(".");
+    if slug.is_empty() {
+        // Names without any ASCII letters still need distinct addresses
+        format!("user.{:08x}", crc32fast::hash(name.as_bytes()))
+    } else {
+        slug
+    }
+}
+
+fn mailbox(name: &str) -> String {
+    let display_name = if name.is_ascii() {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        encode_header(name)
+    };
+    format!("{display_name} <{}>", address(name))
+}
+
+/// Leaves printable ASCII as is and turns anything else into RFC 2047 encoded words.
+fn encode_header(text: &str) -> String {
+    if text.bytes().all(|b| (b' '..=b'~').contains(&b)) {
+        return text.to_string();
+    }
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        if chunk.len() + c.len_utf8() > ENCODED_WORD_BYTES {
+            words.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    words.push(chunk);
+    words
+        .iter()
+        .map(|word| format!("=?UTF-8?B?{}?=", STANDARD.encode(word)))
+        .collect::<Vec<_>>()
+        .join("\r\n ")
+}
+
+fn body_text(message: &Message, missing: &[String]) -> String {
+    let mut lines = Vec::new();
+    if let Some(content) = &message.content {
+        lines.push(content.clone());
+    }
+    if let Some(link) = message.share.as_ref().and_then(|share| share.link.as_ref()) {
+        lines.push(link.clone());
+    }
+    if let Some(call) = message.call_log() {
+        lines.push(format!("Call, {} seconds", call.duration_secs));
+    }
+    if message.is_unsent {
+        lines.push("(unsent)".to_string());
+    }
+    if !message.reactions.is_empty() {
+        let reactions = message
+            .reactions
+            .iter()
+            .map(|reaction| format!("{} {}", reaction.reaction, reaction.actor))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(format!("Reactions: {reactions}"));
+    }
+    for name in missing {
+        lines.push(format!("Missing attachment: {name}"));
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+fn crlf(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = STANDARD.encode(data);
+    let mut lines = String::with_capacity(encoded.len() + encoded.len() / BASE64_LINE_LEN * 2 + 2);
+    for line in encoded.as_bytes().chunks(BASE64_LINE_LEN) {
+        // base64 output is ASCII, so every chunk is valid UTF-8
+        lines.push_str(std::str::from_utf8(line).unwrap_or_default());
+        lines.push_str("\r\n");
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::address;
+    use super::build_mail;
+    use super::encode_header;
+    use super::mbox_entry;
+    use crate::mail_attachment::MailAttachment;
+    use crate::meta::messages::conversation::Conversation;
+    use crate::meta::messages::conversation_part::ConversationPart;
+
+    fn conversation() -> Conversation {
+        let part = ConversationPart::from_json(
+            br#"{
+                "participants": [{"name": "Bob Smith"}, {"name": "Alice"}],
+                "title": "Bob Smith",
+                "messages": [
+                    {"sender_name": "Bob Smith", "timestamp_ms": 1600000000000, "content": "From here on\nall good"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        Conversation::merge("bobsmith_123".to_string(), [part])
+    }
+
+    #[test]
+    fn addresses_are_slugs() {
+        assert_eq!(address("Bob Smith"), "bob.smith@messenger.invalid");
+        assert_eq!(address("  A--b "), "a.b@messenger.invalid");
+        assert!(address("李雷").starts_with("user."));
+    }
+
+    #[test]
+    fn non_ascii_headers_are_encoded() {
+        assert_eq!(encode_header("Hello"), "Hello");
+        assert_eq!(encode_header("Café"), "=?UTF-8?B?Q2Fmw6k=?=");
+        assert!(
+            encode_header(&"é".repeat(60))
+                .split("\r\n ")
+                .all(|word| word.len() <= 75)
+        );
+    }
+
+    #[test]
+    fn builds_plain_and_multipart_mail() {
+        let conversation = conversation();
+        let message = &conversation.messages[0];
+        let plain = build_mail(&conversation, message, &[], &[]);
+        assert!(plain.contains("From: \"Bob Smith\" <bob.smith@messenger.invalid>\r\n"));
+        assert!(plain.contains("To: \"Alice\" <alice@messenger.invalid>\r\n"));
+        assert!(plain.contains("Date: Sun, 13 Sep 2020 12:26:40 +0000\r\n"));
+        assert!(plain.ends_with("\r\n\r\nFrom here on\r\nall good\r\n"));
+
+        let attachment = MailAttachment {
+            file_name: "photo.jpg".to_string(),
+            content_type: "image/jpeg",
+            data: vec![0xFF, 0xD8, 0xFF],
+        };
+        let multipart = build_mail(&conversation, message, &[attachment], &[]);
+        assert!(multipart.contains("Content-Type: multipart/mixed; boundary=\"=_thrumzip_"));
+        assert!(multipart.contains("filename=\"photo.jpg\"\r\n"));
+        assert!(multipart.contains("\r\n\r\n/9j/\r\n"));
+        assert!(multipart.trim_end().ends_with("--"));
+    }
+
+    #[test]
+    fn mbox_quotes_from_lines() {
+        let conversation = conversation();
+        let message = &conversation.messages[0];
+        let entry = mbox_entry(&build_mail(&conversation, message, &[], &[]), message);
+        assert!(entry.starts_with("From bob.smith@messenger.invalid Sun Sep 13 12:26:40 2020\n"));
+        assert!(entry.contains("\n>From here on\n"));
+        assert!(!entry.contains('\r'));
+    }
+}
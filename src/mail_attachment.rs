@@ -0,0 +1,7 @@
+/// A file embedded in an exported message as a MIME part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailAttachment {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub data: Vec<u8>,
+}
@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+
+/// How exported Messenger threads are written.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MailFormat {
+    /// One mbox file per thread
+    #[default]
+    Mbox,
+    /// One `.eml` file per message, in a folder per thread
+    Eml,
+}
@@ -0,0 +1,41 @@
+use crate::existing_file::ExistingFile;
+use crate::perceptual::is_image;
+use itertools::Itertools;
+use std::path::Path;
+
+/// Extensions of video entries found in Meta exports.
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "m4v", "avi", "webm", "3gp", "mkv"];
+
+pub fn is_video(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        VIDEO_EXTENSIONS
+            .iter()
+            .any(|video_ext| ext.eq_ignore_ascii_case(video_ext))
+    })
+}
+
+/// Photos and videos, the files a gallery shows.
+pub fn is_media(path: &Path) -> bool {
+    is_image(path) || is_video(path)
+}
+
+/// The splat files of photos and videos. Disambiguated copies are only used when a name has no canonical file.
+pub fn canonical_media(existing_files: &[ExistingFile]) -> Vec<&ExistingFile> {
+    existing_files
+        .iter()
+        .filter(|file| is_media(file.path_inside_zip()))
+        .into_group_map_by(|file| file.path_inside_zip().to_owned())
+        .into_values()
+        .flat_map(|files| {
+            if files.iter().any(|file| !file.is_ambiguous()) {
+                files
+                    .into_iter()
+                    .filter(|file| !file.is_ambiguous())
+                    .collect_vec()
+            } else {
+                files
+            }
+        })
+        .sorted_by(|a, b| a.disk_path().cmp(b.disk_path()))
+        .collect()
+}
@@ -0,0 +1,34 @@
+use crate::existing_file::ExistingFile;
+use crate::meta::media_metadata::MediaMetadata;
+use crate::path_inside_zip::PathInsideZip;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::debug;
+
+/// Reads every JSON file in the destination and combines the metadata of each referenced media file.
+pub async fn collect_media_metadata(
+    existing_files: &[ExistingFile],
+) -> eyre::Result<HashMap<PathInsideZip, MediaMetadata>> {
+    let mut rtn: HashMap<PathInsideZip, MediaMetadata> = HashMap::new();
+    for file in existing_files.iter().filter(|file| {
+        file.path_inside_zip()
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    }) {
+        let data = tokio::fs::read(file.disk_path()).await?;
+        let Ok(value) = serde_json::from_slice::<Value>(&data) else {
+            debug!(
+                "Skipping {}, it is not valid JSON",
+                file.disk_path().display()
+            );
+            continue;
+        };
+        for (uri, media_metadata) in MediaMetadata::collect(&value) {
+            rtn.entry(PathInsideZip::from(PathBuf::from(uri)))
+                .or_default()
+                .merge(media_metadata);
+        }
+    }
+    Ok(rtn)
+}
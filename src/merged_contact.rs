@@ -0,0 +1,74 @@
+use crate::content_line::escape_text;
+use crate::content_line::fold_line;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+
+/// A contact combined from every export that lists the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedContact {
+    pub name: String,
+    pub phones: BTreeSet<String>,
+    pub emails: BTreeSet<String>,
+    /// The earliest time Meta says the contact was added, in seconds since the epoch
+    pub timestamp: Option<i64>,
+    pub first_export: NaiveDate,
+    pub last_export: NaiveDate,
+}
+impl MergedContact {
+    /// Renders the contact as a vCard 4.0 with CRLF line endings.
+    pub fn to_vcard(&self) -> String {
+        let mut lines = vec![
+            "BEGIN:VCARD".to_string(),
+            "VERSION:4.0".to_string(),
+            format!("FN:{}", escape_text(&self.name)),
+        ];
+        for phone in &self.phones {
+            lines.push(format!("TEL;VALUE=uri:tel:{phone}"));
+        }
+        for email in &self.emails {
+            lines.push(format!("EMAIL:{}", escape_text(email)));
+        }
+        let mut note = format!(
+            "First exported {}, last exported {}",
+            self.first_export, self.last_export
+        );
+        if let Some(added) = self
+            .timestamp
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        {
+            note.push_str(&format!(", added {}", added.date_naive()));
+        }
+        lines.push(format!("NOTE:{}", escape_text(&note)));
+        lines.push("END:VCARD".to_string());
+        lines.iter().map(|line| fold_line(line)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MergedContact;
+    use chrono::NaiveDate;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn renders_vcard() {
+        let contact = MergedContact {
+            name: "Smith, Bob".to_string(),
+            phones: BTreeSet::from(["+15550102000".to_string()]),
+            emails: BTreeSet::new(),
+            timestamp: None,
+            first_export: NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
+            last_export: NaiveDate::from_ymd_opt(2024, 6, 19).unwrap(),
+        };
+        assert_eq!(
+            contact.to_vcard(),
+            "BEGIN:VCARD\r\n\
+             VERSION:4.0\r\n\
+             FN:Smith\\, Bob\r\n\
+             TEL;VALUE=uri:tel:+15550102000\r\n\
+             NOTE:First exported 2021-01-02\\, last exported 2024-06-19\r\n\
+             END:VCARD\r\n"
+        );
+    }
+}
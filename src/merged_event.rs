@@ -0,0 +1,135 @@
+use crate::content_line::escape_text;
+use crate::content_line::fold_line;
+use crate::meta::event::Event;
+use chrono::DateTime;
+use chrono::NaiveDate;
+
+/// Identifies the calendar that wrote the events.
+const PRODID: &str = "-//thrumzip//export-events//EN";
+
+/// An event combined from every export that lists the same name and start time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedEvent {
+    /// The details from the newest export, with the strongest response given in it
+    pub event: Event,
+    pub first_export: NaiveDate,
+    pub last_export: NaiveDate,
+}
+impl MergedEvent {
+    /// A UID that stays the same across runs, so calendars update the event instead of duplicating it.
+    pub fn uid(&self) -> String {
+        format!(
+            "{}-{:08x}@thrumzip",
+            self.event.start_timestamp,
+            crc32fast::hash(self.event.name.as_bytes())
+        )
+    }
+
+    /// Renders the event as a VEVENT with CRLF line endings, with `attendee` as the address that responded.
+    pub fn to_vevent(&self, attendee: &str) -> String {
+        let event = &self.event;
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", self.uid()),
+            format!("DTSTAMP:{}", self.last_export.format("%Y%m%dT000000Z")),
+            format!("DTSTART:{}", format_utc(event.start_timestamp)),
+        ];
+        if let Some(end) = event.end_timestamp {
+            lines.push(format!("DTEND:{}", format_utc(end)));
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&event.name)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        if let Some((latitude, longitude)) = event.geo {
+            lines.push(format!("GEO:{latitude};{longitude}"));
+        }
+        lines.push(format!(
+            "ATTENDEE;PARTSTAT={}:mailto:{attendee}",
+            event.rsvp.partstat()
+        ));
+        lines.push(format!(
+            "COMMENT:{}",
+            escape_text(&format!(
+                "First exported {}, last exported {}",
+                self.first_export, self.last_export
+            ))
+        ));
+        lines.push("END:VEVENT".to_string());
+        lines.iter().map(|line| fold_line(line)).collect()
+    }
+}
+
+/// Wraps events in a VCALENDAR.
+pub fn calendar(events: &[MergedEvent], attendee: &str) -> String {
+    let mut rtn = [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        format!("PRODID:{PRODID}").as_str(),
+    ]
+    .iter()
+    .map(|line| fold_line(line))
+    .collect::<String>();
+    for event in events {
+        rtn.push_str(&event.to_vevent(attendee));
+    }
+    rtn.push_str(&fold_line("END:VCALENDAR"));
+    rtn
+}
+
+fn format_utc(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::MergedEvent;
+    use super::calendar;
+    use crate::meta::event::Event;
+    use crate::meta::event_rsvp::EventRsvp;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn renders_calendar() {
+        let event = MergedEvent {
+            event: Event {
+                name: "Picnic; lunch".to_string(),
+                start_timestamp: 1600000000,
+                end_timestamp: Some(1600003600),
+                description: None,
+                location: Some("Park".to_string()),
+                geo: Some((45.5, -73.5)),
+                rsvp: EventRsvp::Interested,
+            },
+            first_export: NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
+            last_export: NaiveDate::from_ymd_opt(2024, 6, 19).unwrap(),
+        };
+        let uid = event.uid();
+        assert_eq!(
+            calendar(&[event], "me@facebook.invalid"),
+            format!(
+                "BEGIN:VCALENDAR\r\n\
+                 VERSION:2.0\r\n\
+                 PRODID:-//thrumzip//export-events//EN\r\n\
+                 BEGIN:VEVENT\r\n\
+                 UID:{uid}\r\n\
+                 DTSTAMP:20240619T000000Z\r\n\
+                 DTSTART:20200913T122640Z\r\n\
+                 DTEND:20200913T132640Z\r\n\
+                 SUMMARY:Picnic\\; lunch\r\n\
+                 LOCATION:Park\r\n\
+                 GEO:45.5;-73.5\r\n\
+                 ATTENDEE;PARTSTAT=TENTATIVE:mailto:me@facebook.invalid\r\n\
+                 COMMENT:First exported 2021-01-02\\, last exported 2024-06-19\r\n\
+                 END:VEVENT\r\n\
+                 END:VCALENDAR\r\n"
+            )
+        );
+    }
+}
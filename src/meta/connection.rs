@@ -0,0 +1,83 @@
+use crate::meta::mojibake::fix_mojibake_str;
+use crate::meta::records::record_arrays;
+use serde_json::Value;
+
+/// A person in a friends, followers or friend request list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub name: String,
+    /// When Meta says the connection was made, removed or requested, in seconds since the epoch
+    pub timestamp: Option<i64>,
+}
+impl Connection {
+    /// Reads every person from a `friends_and_followers` file.
+    ///
+    /// Facebook records look like `{"name", "timestamp"}`, while Instagram style records keep them in `string_list_data`.
+    pub fn collect(value: &Value) -> Vec<Self> {
+        record_arrays(value)
+            .into_iter()
+            .flat_map(|(_, records)| records)
+            .filter_map(Self::from_record)
+            .collect()
+    }
+
+    fn from_record(record: &Value) -> Option<Self> {
+        let string_data = record.get("string_list_data").and_then(|data| data.get(0));
+        let name = record
+            .get("name")
+            .or_else(|| {
+                record
+                    .get("title")
+                    .filter(|title| title.as_str() != Some(""))
+            })
+            .or_else(|| string_data.and_then(|data| data.get("value")))
+            .and_then(Value::as_str)?;
+        let timestamp = record
+            .get("timestamp")
+            .or_else(|| string_data.and_then(|data| data.get("timestamp")))
+            .and_then(Value::as_i64);
+        Some(Self {
+            name: fix_mojibake_str(name).unwrap_or_else(|| name.to_string()),
+            timestamp,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Connection;
+    use serde_json::json;
+
+    #[test]
+    fn reads_facebook_and_instagram_lists() {
+        let facebook = json!({"friends_v2": [
+            {"name": "Bob", "timestamp": 1600000000},
+            {"name": "Andr\u{c3}\u{a9}", "timestamp": 1500000000},
+            {"timestamp": 1}
+        ]});
+        assert_eq!(
+            Connection::collect(&facebook),
+            vec![
+                Connection {
+                    name: "Bob".to_string(),
+                    timestamp: Some(1600000000)
+                },
+                Connection {
+                    name: "André".to_string(),
+                    timestamp: Some(1500000000)
+                },
+            ]
+        );
+
+        let instagram = json!([{"title": "", "string_list_data": [
+            {"href": "https://www.instagram.com/alice", "value": "alice", "timestamp": 1700000000}
+        ]}]);
+        assert_eq!(
+            Connection::collect(&instagram),
+            vec![Connection {
+                name: "alice".to_string(),
+                timestamp: Some(1700000000)
+            }]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use crate::meta::connection::Connection;
+use crate::meta::mojibake::fix_mojibake_str;
+use serde_json::Value;
+use std::path::Path;
+
+/// A person from an address book, synced contacts or friends list in one export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Contact {
+    pub name: String,
+    pub phones: Vec<String>,
+    pub emails: Vec<String>,
+    /// When Meta says the contact was added, in seconds since the epoch
+    pub timestamp: Option<i64>,
+}
+impl Contact {
+    /// Recognizes address books, synced contacts and friends lists by their path inside the export.
+    pub fn is_contact_file(path: &Path) -> bool {
+        if !path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        {
+            return false;
+        }
+        let path = path.to_string_lossy().replace('\\', "/").to_lowercase();
+        let (folders, file_name) = path.rsplit_once('/').unwrap_or(("", &path));
+        if path.contains("address_book") || folders.split('/').any(|folder| folder == "contacts") {
+            return true;
+        }
+        let in_friends_folder =
+            path.contains("friends_and_followers/") || path.contains("connections/friends/");
+        in_friends_folder
+            && file_name.contains("friends")
+            && !["removed", "rejected", "request"]
+                .iter()
+                .any(|excluded| file_name.contains(excluded))
+    }
+
+    /// Reads every contact from a contacts or friends file.
+    pub fn collect(value: &Value) -> Vec<Self> {
+        let mut rtn = Vec::new();
+        collect_address_book(value, &mut rtn);
+        if rtn.is_empty() {
+            rtn.extend(
+                Connection::collect(value)
+                    .into_iter()
+                    .map(|connection| Contact {
+                        name: connection.name,
+                        timestamp: connection.timestamp,
+                        ..Default::default()
+                    }),
+            );
+        }
+        rtn
+    }
+
+    /// Files a phone number or email address.
+    fn add_point(&mut self, point: &str) {
+        let point = point.trim();
+        if point.is_empty() {
+            return;
+        }
+        if point.contains('@') {
+            self.emails.push(point.to_lowercase());
+        } else if let Some(phone) = normalize_phone(point) {
+            self.phones.push(phone);
+        }
+    }
+}
+
+/// Finds address book records anywhere in the file: Facebook's `{"name", "details": [{"contact_point"}]}`
+/// and Instagram's `{"string_map_data": {"First name", "Surname", "Contact information"}}`.
+fn collect_address_book(value: &Value, rtn: &mut Vec<Contact>) {
+    match value {
+        Value::Object(map) => {
+            if let (Some(name), Some(details)) = (
+                map.get("name").and_then(Value::as_str),
+                map.get("details").and_then(Value::as_array),
+            ) {
+                let mut contact = Contact {
+                    name: fix_text(name),
+                    timestamp: map.get("created_timestamp").and_then(Value::as_i64),
+                    ..Default::default()
+                };
+                for point in details
+                    .iter()
+                    .filter_map(|detail| detail.get("contact_point").and_then(Value::as_str))
+                {
+                    contact.add_point(point);
+                }
+                rtn.push(contact);
+                return;
+            }
+            if let Some(data) = map.get("string_map_data").and_then(Value::as_object) {
+                let field = |key: &str| {
+                    data.get(key)
+                        .and_then(|field| field.get("value"))
+                        .and_then(Value::as_str)
+                        .map(fix_text)
+                        .unwrap_or_default()
+                };
+                let name = format!("{} {}", field("First name"), field("Surname"))
+                    .trim()
+                    .to_string();
+                if !name.is_empty() {
+                    let mut contact = Contact {
+                        name,
+                        timestamp: data
+                            .values()
+                            .find_map(|field| field.get("timestamp").and_then(Value::as_i64))
+                            .filter(|timestamp| *timestamp > 0),
+                        ..Default::default()
+                    };
+                    contact.add_point(&field("Contact information"));
+                    rtn.push(contact);
+                }
+                return;
+            }
+            for item in map.values() {
+                collect_address_book(item, rtn);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_address_book(item, rtn);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keeps the digits of a phone number and a leading `+`, or returns `None` if it has too few digits to be one.
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let digits = phone
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    if digits.len() < 3 {
+        return None;
+    }
+    Some(if phone.trim_start().starts_with('+') {
+        format!("+{digits}")
+    } else {
+        digits
+    })
+}
+
+fn fix_text(text: &str) -> String {
+    fix_mojibake_str(text).unwrap_or_else(|| text.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::Contact;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn recognizes_contact_files() {
+        assert!(Contact::is_contact_file(Path::new(
+            "about_you/your_address_books.json"
+        )));
+        assert!(Contact::is_contact_file(Path::new(
+            "friends_and_followers/friends.json"
+        )));
+        assert!(!Contact::is_contact_file(Path::new(
+            "friends_and_followers/removed_friends.json"
+        )));
+        assert!(!Contact::is_contact_file(Path::new(
+            "friends_and_followers/friend_requests_received.json"
+        )));
+        assert!(Contact::is_contact_file(Path::new(
+            "your_instagram_activity/contacts/synced_contacts.json"
+        )));
+        assert!(!Contact::is_contact_file(Path::new(
+            "your_facebook_activity/close_contacts/settings.json"
+        )));
+    }
+
+    #[test]
+    fn reads_address_books_and_friends() {
+        let facebook = json!({"address_book_v2": {"address_book": [
+            {"name": "Bob", "details": [{"contact_point": "+1 (555) 010-2000"}, {"contact_point": "Bob@Example.com"}], "created_timestamp": 10}
+        ]}});
+        assert_eq!(
+            Contact::collect(&facebook),
+            vec![Contact {
+                name: "Bob".to_string(),
+                phones: vec!["+15550102000".to_string()],
+                emails: vec!["bob@example.com".to_string()],
+                timestamp: Some(10),
+            }]
+        );
+
+        let instagram = json!({"contacts_contact_info": [{"title": "", "string_map_data": {
+            "First name": {"value": "Alice", "timestamp": 0},
+            "Surname": {"value": "Smith", "timestamp": 0},
+            "Contact information": {"value": "555 0100", "timestamp": 0}
+        }}]});
+        assert_eq!(
+            Contact::collect(&instagram),
+            vec![Contact {
+                name: "Alice Smith".to_string(),
+                phones: vec!["5550100".to_string()],
+                ..Default::default()
+            }]
+        );
+
+        let friends = json!({"friends_v2": [{"name": "Carol", "timestamp": 20}]});
+        assert_eq!(
+            Contact::collect(&friends),
+            vec![Contact {
+                name: "Carol".to_string(),
+                timestamp: Some(20),
+                ..Default::default()
+            }]
+        );
+    }
+}
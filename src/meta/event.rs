@@ -0,0 +1,177 @@
+use crate::meta::event_rsvp::EventRsvp;
+use crate::meta::mojibake::fix_mojibake_str;
+use serde_json::Value;
+use std::path::Path;
+
+/// An event the account hosted, was invited to or responded to, as listed in one export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub name: String,
+    /// In seconds since the epoch
+    pub start_timestamp: i64,
+    /// In seconds since the epoch, `None` when Meta leaves it as `0`
+    pub end_timestamp: Option<i64>,
+    pub description: Option<String>,
+    /// The place name and address
+    pub location: Option<String>,
+    /// Latitude and longitude of the place
+    pub geo: Option<(f64, f64)>,
+    pub rsvp: EventRsvp,
+}
+impl Event {
+    /// Recognizes the JSON files in an `events` folder.
+    pub fn is_event_file(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            && path
+                .parent()
+                .is_some_and(|parent| parent.components().any(|c| c.as_os_str() == "events"))
+    }
+
+    /// Reads every event from an events file, skipping records without a start time.
+    ///
+    /// Events are held in arrays such as `your_events_v2`, `events_invited_v2` or, for responses,
+    /// `event_responses_v2.events_joined`, and the key of the array says how the account responded.
+    pub fn collect(value: &Value) -> Vec<Self> {
+        let mut rtn = Vec::new();
+        collect_events(value, None, &mut rtn);
+        rtn
+    }
+
+    fn from_record(record: &Value, rsvp: EventRsvp) -> Option<Self> {
+        let name = record.get("name").and_then(Value::as_str)?;
+        let start_timestamp = record
+            .get("start_timestamp")
+            .and_then(Value::as_i64)
+            .filter(|timestamp| *timestamp > 0)?;
+        let place = record.get("place");
+        let text = |value: Option<&Value>| {
+            value
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(fix_text)
+        };
+        let place_name = text(place.and_then(|place| place.get("name")));
+        let address = text(place.and_then(|place| place.get("address")));
+        // The place name is left out when it is already one of the address parts, as in `Town Hall, 2 Main Street`
+        let location = match (place_name, address) {
+            (Some(name), Some(address)) if !address.split(',').any(|part| part.trim() == name) => {
+                Some(format!("{name}, {address}"))
+            }
+            (name, address) => address.or(name),
+        };
+        let coordinate = place.and_then(|place| place.get("coordinate"));
+        let degrees = |key: &str| coordinate.and_then(|c| c.get(key)).and_then(Value::as_f64);
+        Some(Self {
+            name: fix_text(name.trim()),
+            start_timestamp,
+            end_timestamp: record
+                .get("end_timestamp")
+                .and_then(Value::as_i64)
+                .filter(|end| *end > start_timestamp),
+            description: text(record.get("description")),
+            location,
+            geo: degrees("latitude").zip(degrees("longitude")),
+            rsvp,
+        })
+    }
+}
+
+fn collect_events(value: &Value, rsvp: Option<EventRsvp>, rtn: &mut Vec<Event>) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map {
+                collect_events(item, EventRsvp::from_label(key).or(rsvp), rtn);
+            }
+        }
+        Value::Array(items) => {
+            let Some(rsvp) = rsvp else {
+                return;
+            };
+            rtn.extend(
+                items
+                    .iter()
+                    .filter_map(|record| Event::from_record(record, rsvp)),
+            );
+        }
+        _ => {}
+    }
+}
+
+fn fix_text(text: &str) -> String {
+    fix_mojibake_str(text).unwrap_or_else(|| text.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::Event;
+    use crate::meta::event_rsvp::EventRsvp;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn recognizes_event_files() {
+        assert!(Event::is_event_file(Path::new(
+            "your_facebook_activity/events/your_event_responses.json"
+        )));
+        assert!(Event::is_event_file(Path::new(
+            "events/event_invitations.json"
+        )));
+        assert!(!Event::is_event_file(Path::new("events.json")));
+    }
+
+    #[test]
+    fn reads_hosted_events_and_responses() {
+        let hosted = json!({"your_events_v2": [{
+            "name": "Picnic",
+            "start_timestamp": 1600000000,
+            "end_timestamp": 0,
+            "place": {
+                "name": "Park",
+                "coordinate": {"latitude": 45.5, "longitude": -73.5},
+                "address": "1 Park Road"
+            },
+            "description": "Bring food"
+        }]});
+        assert_eq!(
+            Event::collect(&hosted),
+            vec![Event {
+                name: "Picnic".to_string(),
+                start_timestamp: 1600000000,
+                end_timestamp: None,
+                description: Some("Bring food".to_string()),
+                location: Some("Park, 1 Park Road".to_string()),
+                geo: Some((45.5, -73.5)),
+                rsvp: EventRsvp::Hosting,
+            }]
+        );
+
+        let named_address = json!({"your_events_v2": [{
+            "name": "Concert",
+            "start_timestamp": 1600000000,
+            "place": {"name": "Town Hall", "address": "Town Hall, 2 Main Street"}
+        }]});
+        assert_eq!(
+            Event::collect(&named_address)[0].location.as_deref(),
+            Some("Town Hall, 2 Main Street")
+        );
+
+        let responses = json!({"event_responses_v2": {
+            "events_joined": [{"name": "Concert", "start_timestamp": 1500000000, "end_timestamp": 1500003600}],
+            "events_declined": [{"name": "Meeting", "start_timestamp": 1400000000}],
+            "events_interested": [{"name": "Someday", "start_timestamp": 0}]
+        }});
+        let events = Event::collect(&responses)
+            .into_iter()
+            .map(|event| (event.name, event.end_timestamp, event.rsvp))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                ("Concert".to_string(), Some(1500003600), EventRsvp::Going),
+                ("Meeting".to_string(), None, EventRsvp::Declined),
+            ]
+        );
+    }
+}
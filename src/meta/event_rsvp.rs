@@ -0,0 +1,35 @@
+/// How the account responded to an event, ordered so a response outranks a bare invitation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventRsvp {
+    Invited,
+    Declined,
+    Interested,
+    Going,
+    /// The account created the event
+    Hosting,
+}
+impl EventRsvp {
+    /// Recognizes the response from the key of the array holding the event,
+    /// such as `events_joined` in `your_event_responses.json` or `events_invited_v2`.
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.strip_suffix("_v2").unwrap_or(label);
+        Some(match label {
+            "your_events" => EventRsvp::Hosting,
+            "events_joined" => EventRsvp::Going,
+            "events_interested" => EventRsvp::Interested,
+            "events_declined" => EventRsvp::Declined,
+            "events_invited" | "event_invitations" => EventRsvp::Invited,
+            _ => return None,
+        })
+    }
+
+    /// The iCalendar `PARTSTAT` parameter value for the response.
+    pub fn partstat(&self) -> &'static str {
+        match self {
+            EventRsvp::Invited => "NEEDS-ACTION",
+            EventRsvp::Declined => "DECLINED",
+            EventRsvp::Interested => "TENTATIVE",
+            EventRsvp::Going | EventRsvp::Hosting => "ACCEPTED",
+        }
+    }
+}
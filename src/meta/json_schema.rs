@@ -0,0 +1,237 @@
+use crate::meta::json_type::JsonType;
+use crate::meta::schema_change::SchemaChange;
+use crate::meta::schema_change_kind::SchemaChangeKind;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::path::Component;
+use std::path::Path;
+
+/// The fields seen in one or more JSON files and the types each field had.
+///
+/// Fields are paths such as `messages[].sender_name`, where `[]` stands for the elements of an array.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonSchema {
+    pub fields: BTreeMap<String, BTreeSet<JsonType>>,
+}
+impl JsonSchema {
+    pub fn from_value(value: &Value) -> Self {
+        let mut rtn = Self::default();
+        rtn.observe("", value);
+        rtn
+    }
+
+    fn observe(&mut self, path: &str, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, item) in map {
+                    let field = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    self.fields
+                        .entry(field.clone())
+                        .or_default()
+                        .insert(JsonType::of(item));
+                    self.observe(&field, item);
+                }
+            }
+            Value::Array(items) => {
+                let field = format!("{path}[]");
+                for item in items {
+                    self.fields
+                        .entry(field.clone())
+                        .or_default()
+                        .insert(JsonType::of(item));
+                    self.observe(&field, item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Combines the fields of another file of the same kind.
+    pub fn merge(&mut self, other: JsonSchema) {
+        for (field, types) in other.fields {
+            self.fields.entry(field).or_default().extend(types);
+        }
+    }
+
+    /// Fields added, removed or changed in type from `old` to `new`.
+    ///
+    /// A field only counts as added or removed when its parent object was seen in both, and the elements of arrays
+    /// are never counted as added or removed, since an array that happens to be empty says nothing about the format.
+    /// A field that is sometimes `null` does not count as a type change.
+    pub fn diff(old: &JsonSchema, new: &JsonSchema) -> Vec<SchemaChange> {
+        let mut rtn = Vec::new();
+        let fields = old
+            .fields
+            .keys()
+            .chain(new.fields.keys())
+            .collect::<BTreeSet<_>>();
+        for field in fields {
+            let old_types = old.fields.get(field);
+            let new_types = new.fields.get(field);
+            let change = match (old_types, new_types) {
+                (Some(old_types), Some(new_types)) => {
+                    let old_significant = significant(old_types);
+                    let new_significant = significant(new_types);
+                    if old_significant.is_empty()
+                        || new_significant.is_empty()
+                        || old_significant == new_significant
+                    {
+                        continue;
+                    }
+                    SchemaChangeKind::TypeChanged
+                }
+                (None, Some(_)) if is_comparable(old, field) => SchemaChangeKind::Added,
+                (Some(_), None) if is_comparable(new, field) => SchemaChangeKind::Removed,
+                _ => continue,
+            };
+            rtn.push(SchemaChange {
+                field: field.clone(),
+                change,
+                old_types: old_types.into_iter().flatten().copied().collect(),
+                new_types: new_types.into_iter().flatten().copied().collect(),
+            });
+        }
+        rtn
+    }
+}
+
+/// Whether the absence of `field` from `schema` means something, because its parent object was seen.
+fn is_comparable(schema: &JsonSchema, field: &str) -> bool {
+    if field.ends_with("[]") {
+        return false;
+    }
+    let parent = field
+        .rsplit_once('.')
+        .map(|(parent, _)| parent)
+        .unwrap_or_default();
+    parent.is_empty()
+        || schema
+            .fields
+            .get(parent)
+            .is_some_and(|types| types.contains(&JsonType::Object))
+}
+
+/// The types other than `null`, which says nothing about the type a field has when it is set.
+fn significant(types: &BTreeSet<JsonType>) -> BTreeSet<JsonType> {
+    types
+        .iter()
+        .copied()
+        .filter(|json_type| *json_type != JsonType::Null)
+        .collect()
+}
+
+/// Groups files of the same kind across threads, albums and numbered parts.
+///
+/// Folders that are numbers or end in `_<number>`, such as Messenger thread folders, become `*`,
+/// and runs of digits in the file name become `*`, so `messages/inbox/bob_123/message_2.json` is `messages/inbox/*/message_*.json`.
+pub fn schema_pattern(path: &Path) -> String {
+    let components = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let last = components.len().saturating_sub(1);
+    components
+        .iter()
+        .enumerate()
+        .map(|(index, component)| {
+            if index == last {
+                replace_digit_runs(component)
+            } else if is_variable_folder(component) {
+                "*".to_string()
+            } else {
+                component.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_variable_folder(name: &str) -> bool {
+    let is_number = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+    is_number(name)
+        || name
+            .rsplit_once('_')
+            .is_some_and(|(_, tail)| is_number(tail))
+}
+
+fn replace_digit_runs(name: &str) -> String {
+    let mut rtn = String::with_capacity(name.len());
+    let mut in_digits = false;
+    for c in name.chars() {
+        if c.is_ascii_digit() {
+            if !in_digits {
+                rtn.push('*');
+            }
+            in_digits = true;
+        } else {
+            rtn.push(c);
+            in_digits = false;
+        }
+    }
+    rtn
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsonSchema;
+    use super::schema_pattern;
+    use crate::meta::schema_change_kind::SchemaChangeKind;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn patterns_group_threads_and_parts() {
+        assert_eq!(
+            schema_pattern(Path::new("messages/inbox/bob_1234567890/message_12.json")),
+            "messages/inbox/*/message_*.json"
+        );
+        assert_eq!(
+            schema_pattern(Path::new("posts/album/3.json")),
+            "posts/album/*.json"
+        );
+        assert_eq!(
+            schema_pattern(Path::new("friends_and_followers/friends.json")),
+            "friends_and_followers/friends.json"
+        );
+    }
+
+    #[test]
+    fn reports_added_removed_and_retyped_fields() {
+        let old = JsonSchema::from_value(&json!({
+            "title": "a",
+            "messages": [{"sender_name": "Bob", "timestamp_ms": 1, "photos": []}],
+            "thread_type": "Regular"
+        }));
+        let new = JsonSchema::from_value(&json!({
+            "title": null,
+            "messages": [{"sender_name": "Bob", "timestamp_ms": "1", "is_unsent": false}],
+            "joinable_mode": {"mode": 1}
+        }));
+        let changes = JsonSchema::diff(&old, &new)
+            .into_iter()
+            .map(|change| (change.field, change.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                ("joinable_mode".to_string(), SchemaChangeKind::Added),
+                ("messages[].is_unsent".to_string(), SchemaChangeKind::Added),
+                ("messages[].photos".to_string(), SchemaChangeKind::Removed),
+                (
+                    "messages[].timestamp_ms".to_string(),
+                    SchemaChangeKind::TypeChanged
+                ),
+                ("thread_type".to_string(), SchemaChangeKind::Removed),
+            ]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use crate::meta::uris::is_web_link;
+use serde_json::Value;
+
+/// What Meta's JSON says about a media file it references.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MediaMetadata {
+    /// When the photo was taken, from `media_metadata.photo_metadata.exif_data`, in seconds since the epoch
+    pub taken_timestamp: Option<i64>,
+    /// When the media was uploaded, in seconds since the epoch
+    pub creation_timestamp: Option<i64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+impl MediaMetadata {
+    /// The best guess at when the media was captured.
+    pub fn timestamp(&self) -> Option<i64> {
+        self.taken_timestamp
+            .filter(|taken| *taken > 0)
+            .or(self.creation_timestamp)
+    }
+
+    pub fn gps(&self) -> Option<(f64, f64)> {
+        match (self.latitude, self.longitude) {
+            // Meta writes 0,0 when it has no location
+            (Some(latitude), Some(longitude)) if latitude != 0.0 || longitude != 0.0 => {
+                Some((latitude, longitude))
+            }
+            _ => None,
+        }
+    }
+
+    /// Fills fields that are missing here from another reference to the same file.
+    pub fn merge(&mut self, other: MediaMetadata) {
+        // A zero taken time means the EXIF data had none, it must not hide a real one from another reference
+        self.taken_timestamp = self
+            .taken_timestamp
+            .filter(|taken| *taken > 0)
+            .or(other.taken_timestamp.filter(|taken| *taken > 0));
+        self.creation_timestamp = self.creation_timestamp.or(other.creation_timestamp);
+        if self.gps().is_none() {
+            self.latitude = other.latitude;
+            self.longitude = other.longitude;
+        }
+    }
+
+    /// Finds every object with a `uri` in a JSON file and reads the metadata next to it.
+    pub fn collect(value: &Value) -> Vec<(String, MediaMetadata)> {
+        let mut rtn = Vec::new();
+        collect_into(value, &mut rtn);
+        rtn
+    }
+}
+
+fn collect_into(value: &Value, out: &mut Vec<(String, MediaMetadata)>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(uri) = map
+                .get("uri")
+                .and_then(Value::as_str)
+                .filter(|uri| !is_web_link(uri))
+            {
+                let exif = map
+                    .get("media_metadata")
+                    .and_then(|metadata| metadata.get("photo_metadata"))
+                    .and_then(|metadata| metadata.get("exif_data"))
+                    .and_then(Value::as_array)
+                    .and_then(|exif| exif.first());
+                let exif_field = |key: &str| exif.and_then(|exif| exif.get(key));
+                out.push((
+                    uri.to_string(),
+                    MediaMetadata {
+                        taken_timestamp: exif_field("taken_timestamp").and_then(Value::as_i64),
+                        creation_timestamp: map.get("creation_timestamp").and_then(Value::as_i64),
+                        latitude: exif_field("latitude").and_then(Value::as_f64),
+                        longitude: exif_field("longitude").and_then(Value::as_f64),
+                    },
+                ));
+            }
+            for item in map.values() {
+                collect_into(item, out);
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_into(item, out)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MediaMetadata;
+    use serde_json::json;
+
+    #[test]
+    fn collects_photo_metadata() {
+        let value = json!([{
+            "attachments": [{"data": [{"media": {
+                "uri": "posts/media/album/1.jpg",
+                "creation_timestamp": 1600000000,
+                "media_metadata": {"photo_metadata": {"exif_data": [
+                    {"taken_timestamp": 1500000000, "latitude": 51.5, "longitude": -0.12}
+                ]}}
+            }}]}]
+        }, {
+            "photos": [{"uri": "messages/inbox/bob_1/photos/2.jpg", "creation_timestamp": 1400000000}],
+            "sticker": {"uri": "https://example.com/sticker.png"}
+        }]);
+        let collected = MediaMetadata::collect(&value);
+        assert_eq!(collected.len(), 2);
+        assert_eq!(collected[0].0, "posts/media/album/1.jpg");
+        assert_eq!(collected[0].1.timestamp(), Some(1500000000));
+        assert_eq!(collected[0].1.gps(), Some((51.5, -0.12)));
+        assert_eq!(collected[1].1.timestamp(), Some(1400000000));
+        assert_eq!(collected[1].1.gps(), None);
+    }
+
+    #[test]
+    fn merge_skips_missing_taken_timestamps() {
+        let mut merged = MediaMetadata {
+            taken_timestamp: Some(0),
+            creation_timestamp: Some(1600000000),
+            ..Default::default()
+        };
+        merged.merge(MediaMetadata {
+            taken_timestamp: Some(1500000000),
+            ..Default::default()
+        });
+        assert_eq!(merged.taken_timestamp, Some(1500000000));
+
+        merged.merge(MediaMetadata {
+            taken_timestamp: Some(1400000000),
+            ..Default::default()
+        });
+        assert_eq!(merged.timestamp(), Some(1500000000));
+    }
+}
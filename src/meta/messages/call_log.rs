@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+/// A voice or video call recorded in a thread.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CallLog {
+    pub caller: String,
+    pub timestamp_ms: i64,
+    pub duration_secs: u64,
+}
+impl CallLog {
+    /// Meta records unanswered calls with a duration of zero.
+    pub fn is_missed(&self) -> bool {
+        self.duration_secs == 0
+    }
+}
@@ -0,0 +1,205 @@
+use crate::meta::messages::call_log::CallLog;
+use crate::meta::messages::conversation_part::ConversationPart;
+use crate::meta::messages::message::Message;
+use crate::meta::messages::participant::Participant;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A Messenger thread merged from every `message_N.json` part in every export.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Conversation {
+    /// Name of the thread folder, such as `bob_1234567890`, which stays the same across exports
+    pub thread_key: String,
+    pub title: Option<String>,
+    pub participants: Vec<Participant>,
+    /// Messages in chronological order, without duplicates
+    pub messages: Vec<Message>,
+}
+impl Conversation {
+    /// The thread a message file belongs to, taken from its folder name.
+    pub fn thread_key(path_inside_zip: &Path) -> Option<String> {
+        Some(
+            path_inside_zip
+                .parent()?
+                .file_name()?
+                .to_string_lossy()
+                .to_string(),
+        )
+    }
+
+    /// Merges the parts of one thread. Parts should be given oldest export first, so the newest title wins.
+    ///
+    /// Messages are deduplicated by sender, timestamp and content hash; reactions of duplicates are combined.
+    pub fn merge(thread_key: String, parts: impl IntoIterator<Item = ConversationPart>) -> Self {
+        let mut title = None;
+        let mut participants: Vec<Participant> = Vec::new();
+        let mut messages: Vec<Message> = Vec::new();
+        let mut index_by_key: HashMap<(String, i64, u32), usize> = HashMap::new();
+        for part in parts {
+            if part.title.is_some() {
+                title = part.title;
+            }
+            for participant in part.participants {
+                if !participants.contains(&participant) {
+                    participants.push(participant);
+                }
+            }
+            for message in part.messages {
+                let key = (
+                    message.sender_name.clone(),
+                    message.timestamp_ms,
+                    message.content_hash(),
+                );
+                match index_by_key.get(&key) {
+                    Some(&index) => {
+                        let existing = &mut messages[index];
+                        for reaction in message.reactions {
+                            if !existing.reactions.contains(&reaction) {
+                                existing.reactions.push(reaction);
+                            }
+                        }
+                        existing.is_unsent |= message.is_unsent;
+                    }
+                    None => {
+                        index_by_key.insert(key, messages.len());
+                        messages.push(message);
+                    }
+                }
+            }
+        }
+        messages.sort_by_key(|message| message.timestamp_ms);
+        Self {
+            thread_key,
+            title,
+            participants,
+            messages,
+        }
+    }
+
+    /// Groups parts by thread and merges each thread. Parts should be given oldest export first.
+    pub fn merge_all(parts: impl IntoIterator<Item = (String, ConversationPart)>) -> Vec<Self> {
+        parts
+            .into_iter()
+            .into_group_map()
+            .into_iter()
+            .map(|(thread_key, parts)| Self::merge(thread_key, parts))
+            .sorted_by(|a, b| a.thread_key.cmp(&b.thread_key))
+            .collect()
+    }
+
+    pub fn call_logs(&self) -> Vec<CallLog> {
+        self.messages.iter().filter_map(Message::call_log).collect()
+    }
+
+    /// Distinct senders, in order of their first message.
+    pub fn senders(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.messages
+            .iter()
+            .map(|message| message.sender_name.as_str())
+            .filter(|sender| seen.insert(*sender))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Conversation;
+    use crate::meta::messages::conversation_part::ConversationPart;
+    use std::path::Path;
+
+    fn part(json: &str) -> ConversationPart {
+        ConversationPart::from_json(json.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn merges_parts_across_exports() {
+        let old_export = part(
+            r#"{
+                "participants": [{"name": "Alice"}, {"name": "Bob"}],
+                "messages": [
+                    {"sender_name": "Bob", "timestamp_ms": 2000, "content": "Hi Alice"},
+                    {"sender_name": "Alice", "timestamp_ms": 1000, "content": "Hi Bob"}
+                ],
+                "title": "Bob"
+            }"#,
+        );
+        let new_export = part(
+            r#"{
+                "participants": [{"name": "Alice"}, {"name": "Bob"}],
+                "messages": [
+                    {"sender_name": "Alice", "timestamp_ms": 3000, "photos": [{"uri": "your_activity/messages/inbox/bob_1/photos/1.jpg"}]},
+                    {"sender_name": "Bob", "timestamp_ms": 2000, "content": "Hi Alice", "reactions": [{"reaction": "â\u009d¤", "actor": "Alice"}]},
+                    {"sender_name": "Alice", "timestamp_ms": 1000, "content": "Hi Bob"}
+                ],
+                "title": "Bob Builder"
+            }"#,
+        );
+        let conversation = Conversation::merge("bob_1".to_string(), [old_export, new_export]);
+        assert_eq!(conversation.title.as_deref(), Some("Bob Builder"));
+        assert_eq!(conversation.participants.len(), 2);
+        assert_eq!(
+            conversation
+                .messages
+                .iter()
+                .map(|message| message.timestamp_ms)
+                .collect::<Vec<_>>(),
+            vec![1000, 2000, 3000]
+        );
+        assert_eq!(conversation.messages[1].reactions.len(), 1);
+        assert_eq!(conversation.messages[1].reactions[0].reaction, "❤");
+        assert_eq!(conversation.senders(), vec!["Alice", "Bob"]);
+    }
+
+    #[test]
+    fn attachments_match_across_folder_moves() {
+        let old_export = part(
+            r#"{"messages": [{"sender_name": "Alice", "timestamp_ms": 1, "photos": [{"uri": "messages/inbox/bob_1/photos/1.jpg"}]}]}"#,
+        );
+        let new_export = part(
+            r#"{"messages": [{"sender_name": "Alice", "timestamp_ms": 1, "photos": [{"uri": "your_activity_across_facebook/messages/inbox/bob_1/photos/1.jpg"}]}]}"#,
+        );
+        let conversation = Conversation::merge("bob_1".to_string(), [old_export, new_export]);
+        assert_eq!(conversation.messages.len(), 1);
+    }
+
+    #[test]
+    fn thread_key_is_folder_name() {
+        assert_eq!(
+            Conversation::thread_key(Path::new("messages/inbox/bob_1/message_1.json")).as_deref(),
+            Some("bob_1")
+        );
+    }
+
+    #[test]
+    fn keeps_call_logs() {
+        let conversation = Conversation::merge(
+            "bob_1".to_string(),
+            [part(
+                r#"{"messages": [{"sender_name": "Bob", "timestamp_ms": 5, "content": "Bob called you.", "call_duration": 0}]}"#,
+            )],
+        );
+        let calls = conversation.call_logs();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].is_missed());
+    }
+
+    #[test]
+    fn keeps_messages_without_a_sender() {
+        let sent = part(
+            r#"{"messages": [
+                {"timestamp_ms": 2, "content": "From a deleted account"},
+                {"sender_name": "Bob", "timestamp_ms": 1, "content": "Hi"}
+            ]}"#,
+        );
+        assert_eq!(sent.messages.len(), 2);
+        assert_eq!(sent.messages[0].sender_name, "");
+        assert_eq!(
+            sent.messages[0].content.as_deref(),
+            Some("From a deleted account")
+        );
+    }
+}
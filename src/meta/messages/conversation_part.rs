@@ -0,0 +1,32 @@
+use crate::meta::messages::message::Message;
+use crate::meta::messages::participant::Participant;
+use crate::meta::mojibake::fix_mojibake_value;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+/// The contents of one `message_N.json` file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConversationPart {
+    #[serde(default)]
+    pub participants: Vec<Participant>,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub is_still_participant: Option<bool>,
+    #[serde(default)]
+    pub thread_path: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+impl ConversationPart {
+    /// Parses a message file, repairing Meta's text encoding first.
+    pub fn from_json(data: &[u8]) -> eyre::Result<Self> {
+        let mut value: Value = serde_json::from_slice(data)?;
+        fix_mojibake_value(&mut value);
+        Ok(serde_json::from_value(value)?)
+    }
+}
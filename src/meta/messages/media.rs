@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+/// An attached photo, video, audio file, gif, sticker or file, referenced by its path in the export.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Media {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creation_timestamp: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+impl Media {
+    /// The file name part of the uri, which stays the same when Meta moves the folder between exports.
+    pub fn file_name(&self) -> &str {
+        self.uri.rsplit(['/', '\\']).next().unwrap_or(&self.uri)
+    }
+}
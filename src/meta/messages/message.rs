@@ -0,0 +1,80 @@
+use crate::meta::messages::call_log::CallLog;
+use crate::meta::messages::media::Media;
+use crate::meta::messages::reaction::Reaction;
+use crate::meta::messages::share::Share;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+/// One message of a Messenger thread. Fields this model does not know about are kept in `extra`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Message {
+    /// Empty when Meta leaves the sender out, as it does for some deleted accounts
+    #[serde(default)]
+    pub sender_name: String,
+    pub timestamp_ms: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub photos: Vec<Media>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub videos: Vec<Media>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audio_files: Vec<Media>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gifs: Vec<Media>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<Media>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sticker: Option<Media>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share: Option<Share>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reactions: Vec<Reaction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_duration: Option<u64>,
+    #[serde(default)]
+    pub is_unsent: bool,
+    /// `Generic`, `Share`, `Call` and so on in older exports
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+impl Message {
+    /// Every attachment, in a stable order.
+    pub fn media(&self) -> impl Iterator<Item = &Media> {
+        self.photos
+            .iter()
+            .chain(&self.videos)
+            .chain(&self.audio_files)
+            .chain(&self.gifs)
+            .chain(&self.files)
+            .chain(&self.sticker)
+    }
+
+    /// Hash of what the message says, used to recognize the same message in different exports.
+    /// Attachments contribute their file names, since Meta moves the folders they live in between exports.
+    pub fn content_hash(&self) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(self.content.as_deref().unwrap_or_default().as_bytes());
+        for media in self.media() {
+            hasher.update(b"\0");
+            hasher.update(media.file_name().as_bytes());
+        }
+        if let Some(link) = self.share.as_ref().and_then(|share| share.link.as_deref()) {
+            hasher.update(b"\0");
+            hasher.update(link.as_bytes());
+        }
+        hasher.finalize()
+    }
+
+    pub fn call_log(&self) -> Option<CallLog> {
+        self.call_duration.map(|duration_secs| CallLog {
+            caller: self.sender_name.clone(),
+            timestamp_ms: self.timestamp_ms,
+            duration_secs,
+        })
+    }
+}
@@ -0,0 +1,8 @@
+pub mod call_log;
+pub mod conversation;
+pub mod conversation_part;
+pub mod media;
+pub mod message;
+pub mod participant;
+pub mod reaction;
+pub mod share;
@@ -0,0 +1,17 @@
+pub mod connection;
+pub mod contact;
+pub mod event;
+pub mod event_rsvp;
+pub mod json_schema;
+pub mod json_type;
+pub mod media_metadata;
+pub mod messages;
+pub mod mojibake;
+pub mod record_diff;
+pub mod record_kind;
+pub mod records;
+pub mod schema_change;
+pub mod schema_change_kind;
+pub mod text_kind;
+pub mod text_record;
+pub mod uris;
@@ -0,0 +1,87 @@
+use serde_json::Map;
+use serde_json::Value;
+
+/// Repairs a string whose UTF-8 bytes were escaped as Latin-1 code points, as Meta does in its JSON exports (`Ã©` instead of `é`).
+///
+/// Returns `None` when the string does not look double encoded or is already correct.
+pub fn fix_mojibake_str(s: &str) -> Option<String> {
+    if s.is_ascii() || s.chars().any(|c| c as u32 > 0xFF) {
+        return None;
+    }
+    let bytes = s.chars().map(|c| c as u8).collect::<Vec<_>>();
+    String::from_utf8(bytes).ok()
+}
+
+/// Walks every key and string value, repairing them in place. Returns the number of strings repaired.
+pub fn fix_mojibake_value(value: &mut Value) -> usize {
+    match value {
+        Value::String(s) => match fix_mojibake_str(s) {
+            Some(fixed) => {
+                *s = fixed;
+                1
+            }
+            None => 0,
+        },
+        Value::Array(items) => items.iter_mut().map(fix_mojibake_value).sum(),
+        Value::Object(map) => {
+            let mut fixed = 0;
+            let entries = std::mem::take(map);
+            let mut rebuilt = Map::with_capacity(entries.len());
+            for (key, mut item) in entries {
+                fixed += fix_mojibake_value(&mut item);
+                let key = match fix_mojibake_str(&key) {
+                    Some(fixed_key) => {
+                        fixed += 1;
+                        fixed_key
+                    }
+                    None => key,
+                };
+                rebuilt.insert(key, item);
+            }
+            *map = rebuilt;
+            fixed
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+    }
+}
+
+/// Transcodes a JSON document, returning the repaired bytes or `None` if nothing needed repairing.
+pub fn fix_mojibake_json(data: &[u8]) -> eyre::Result<Option<Vec<u8>>> {
+    let mut value: Value = serde_json::from_slice(data)?;
+    if fix_mojibake_value(&mut value) == 0 {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_vec_pretty(&value)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::fix_mojibake_json;
+    use super::fix_mojibake_str;
+
+    #[test]
+    fn fixes_double_encoding() {
+        assert_eq!(fix_mojibake_str("Caf\u{c3}\u{a9}").as_deref(), Some("Café"));
+        assert_eq!(
+            fix_mojibake_str("\u{f0}\u{9f}\u{98}\u{80}").as_deref(),
+            Some("😀")
+        );
+    }
+
+    #[test]
+    fn leaves_correct_text_alone() {
+        assert_eq!(fix_mojibake_str("plain"), None);
+        assert_eq!(fix_mojibake_str("Café"), None);
+        assert_eq!(fix_mojibake_str("😀"), None);
+    }
+
+    #[test]
+    fn fixes_json_documents() -> eyre::Result<()> {
+        let raw = br#"{"sender_name": "Ren\u00c3\u00a9e", "content": "hi"}"#;
+        let fixed = fix_mojibake_json(raw)?.expect("should need repair");
+        let value: serde_json::Value = serde_json::from_slice(&fixed)?;
+        assert_eq!(value["sender_name"], "Renée");
+        assert_eq!(fix_mojibake_json(br#"{"content": "hi"}"#)?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::meta::records::canonical_record;
+use crate::meta::records::record_arrays;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// How the records of two versions of a Meta JSON file differ.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordDiff {
+    pub added: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+impl std::fmt::Display for RecordDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "records +{} -{} ={}",
+            self.added, self.removed, self.unchanged
+        )
+    }
+}
+
+/// Compares the records of two versions of a JSON file, treating each record array as a multiset.
+pub fn diff_records(old: &Value, new: &Value) -> RecordDiff {
+    let mut counts: HashMap<(&str, String), i64> = HashMap::new();
+    for (label, records) in record_arrays(old) {
+        for record in records {
+            *counts.entry((label, canonical_record(record))).or_default() -= 1;
+        }
+    }
+    let mut rtn = RecordDiff::default();
+    for (label, records) in record_arrays(new) {
+        for record in records {
+            let count = counts.entry((label, canonical_record(record))).or_default();
+            if *count < 0 {
+                rtn.unchanged += 1;
+            } else {
+                rtn.added += 1;
+            }
+            *count += 1;
+        }
+    }
+    rtn.removed = counts
+        .values()
+        .filter(|count| **count < 0)
+        .map(|count| count.unsigned_abs() as usize)
+        .sum();
+    rtn
+}
+
+/// Parses both versions and compares their records.
+pub fn diff_json_records(old: &[u8], new: &[u8]) -> eyre::Result<RecordDiff> {
+    let old: Value = serde_json::from_slice(old)?;
+    let new: Value = serde_json::from_slice(new)?;
+    Ok(diff_records(&old, &new))
+}
+
+#[cfg(test)]
+mod test {
+    use super::RecordDiff;
+    use super::diff_json_records;
+
+    #[test]
+    fn counts_added_and_removed_records() -> eyre::Result<()> {
+        let old = br#"{"title": "x", "messages": [{"a": 1}, {"a": 2}, {"a": 3}]}"#;
+        let new = br#"{"title": "y", "messages": [{"a": 1}, {"a": 3}, {"a": 4}, {"a": 5}]}"#;
+        assert_eq!(
+            diff_json_records(old, new)?,
+            RecordDiff {
+                added: 2,
+                removed: 1,
+                unchanged: 2
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn key_order_does_not_matter() -> eyre::Result<()> {
+        let old = br#"[{"a": 1, "b": {"c": 2, "d": 3}}]"#;
+        let new = br#"[{"b": {"d": 3, "c": 2}, "a": 1}]"#;
+        assert_eq!(
+            diff_json_records(old, new)?,
+            RecordDiff {
+                added: 0,
+                removed: 0,
+                unchanged: 1
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicates_are_counted() -> eyre::Result<()> {
+        let old = br#"[{"a": 1}, {"a": 1}]"#;
+        let new = br#"[{"a": 1}]"#;
+        assert_eq!(
+            diff_json_records(old, new)?,
+            RecordDiff {
+                added: 0,
+                removed: 1,
+                unchanged: 1
+            }
+        );
+        Ok(())
+    }
+}
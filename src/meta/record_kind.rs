@@ -0,0 +1,44 @@
+use crate::meta::text_kind::TextKind;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The kinds of Meta JSON whose records are tracked across exports.
+#[derive(Serialize, ValueEnum, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    Message,
+    Post,
+    Comment,
+    Reaction,
+}
+impl RecordKind {
+    /// Recognizes the kind of a JSON file from its path inside the export.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        if let Some(kind) = TextKind::from_path(path) {
+            return Some(match kind {
+                TextKind::Message => RecordKind::Message,
+                TextKind::Post => RecordKind::Post,
+                TextKind::Comment => RecordKind::Comment,
+            });
+        }
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let path = path.to_string_lossy().replace('\\', "/").to_lowercase();
+        if is_json && path.contains("likes_and_reactions") {
+            return Some(RecordKind::Reaction);
+        }
+        None
+    }
+
+    /// The files whose records are compared together. A thread's messages move between
+    /// `message_1.json`, `message_2.json` and so on as it grows, so they are grouped by thread folder.
+    pub fn group(&self, path: &Path) -> PathBuf {
+        match self {
+            RecordKind::Message => path.parent().unwrap_or(path).to_path_buf(),
+            _ => path.to_path_buf(),
+        }
+    }
+}
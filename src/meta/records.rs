@@ -0,0 +1,128 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Finds the arrays of records in a Meta JSON file, labelled by the key holding them.
+///
+/// Meta files are either a top-level array (posts) or an object whose array-valued keys hold the records
+/// (`messages`, `comments_v2`, `friends_v2`, ...). A top-level array is labelled with an empty string.
+pub fn record_arrays(value: &Value) -> Vec<(&str, &[Value])> {
+    match value {
+        Value::Array(items) => vec![("", items.as_slice())],
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(key, item)| match item {
+                Value::Array(items) => Some((key.as_str(), items.as_slice())),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Serializes a record with object keys sorted, so records that only differ in key order compare equal.
+pub fn canonical_record(value: &Value) -> String {
+    fn sort_keys(value: &Value) -> Value {
+        match value {
+            Value::Array(items) => Value::Array(items.iter().map(sort_keys).collect()),
+            Value::Object(map) => {
+                let sorted = map
+                    .iter()
+                    .map(|(key, item)| (key.clone(), sort_keys(item)))
+                    .collect::<BTreeMap<_, _>>();
+                Value::Object(sorted.into_iter().collect())
+            }
+            other => other.clone(),
+        }
+    }
+    sort_keys(value).to_string()
+}
+
+/// A key identifying a record across exports, so a record whose content Meta later changed is still recognized.
+///
+/// Messages are identified by sender and `timestamp_ms`, posts, comments and reactions by `timestamp` and `title`,
+/// and anything else by its full content.
+pub fn record_identity(record: &Value) -> String {
+    if let Some(timestamp_ms) = record.get("timestamp_ms").and_then(Value::as_i64) {
+        let sender = record
+            .get("sender_name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        return format!("{sender}\0{timestamp_ms}");
+    }
+    if let Some(timestamp) = record.get("timestamp").and_then(Value::as_i64) {
+        let title = record
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        return format!("{title}\0{timestamp}");
+    }
+    canonical_record(record)
+}
+
+/// A short human readable description of a record: its text, or else its title, truncated.
+pub fn record_summary(record: &Value, max_chars: usize) -> String {
+    let text = ["content", "post", "comment", "title", "name"]
+        .iter()
+        .find_map(|key| find_string(record, key))
+        .unwrap_or_default();
+    let mut summary = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.chars().count() > max_chars {
+        summary = summary.chars().take(max_chars).collect::<String>() + "...";
+    }
+    summary
+}
+
+/// Whether a message record was unsent, which newer exports show by keeping the message without its content.
+pub fn is_unsent(record: &Value) -> bool {
+    record
+        .get("is_unsent")
+        .and_then(Value::as_bool)
+        .unwrap_or_default()
+}
+
+/// Finds the first non-empty string under a key, searching nested objects and arrays depth first.
+fn find_string<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .and_then(Value::as_str)
+            .filter(|text| !text.is_empty())
+            .or_else(|| map.values().find_map(|item| find_string(item, key))),
+        Value::Array(items) => items.iter().find_map(|item| find_string(item, key)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::record_identity;
+    use super::record_summary;
+    use serde_json::json;
+
+    #[test]
+    fn identity_survives_content_changes() {
+        let sent =
+            json!({"sender_name": "Bob", "timestamp_ms": 1, "content": "hi", "reactions": []});
+        let unsent = json!({"sender_name": "Bob", "timestamp_ms": 1, "is_unsent": true});
+        assert_eq!(record_identity(&sent), record_identity(&unsent));
+
+        let post =
+            json!({"timestamp": 5, "title": "Bob updated his status.", "data": [{"post": "a"}]});
+        let edited =
+            json!({"timestamp": 5, "title": "Bob updated his status.", "data": [{"post": "b"}]});
+        assert_eq!(record_identity(&post), record_identity(&edited));
+
+        assert_ne!(
+            record_identity(&json!({"a": 1})),
+            record_identity(&json!({"a": 2}))
+        );
+    }
+
+    #[test]
+    fn summarizes_nested_text() {
+        let comment = json!({"timestamp": 5, "data": [{"comment": {"comment": "Nice\n  photo!", "author": "Bob"}}]});
+        assert_eq!(record_summary(&comment, 80), "Nice photo!");
+        let post = json!({"data": [{"post": "abcdef"}]});
+        assert_eq!(record_summary(&post, 3), "abc...");
+    }
+}
@@ -0,0 +1,13 @@
+use crate::meta::json_type::JsonType;
+use crate::meta::schema_change_kind::SchemaChangeKind;
+use serde::Serialize;
+
+/// A field whose presence or type differs between two versions of a JSON file.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    /// Path of the field, such as `messages[].reactions[].actor`
+    pub field: String,
+    pub change: SchemaChangeKind,
+    pub old_types: Vec<JsonType>,
+    pub new_types: Vec<JsonType>,
+}
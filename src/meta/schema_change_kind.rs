@@ -0,0 +1,19 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaChangeKind {
+    Added,
+    Removed,
+    TypeChanged,
+}
+impl SchemaChangeKind {
+    /// Prefix used in the table output, like a unified diff.
+    pub fn symbol(&self) -> char {
+        match self {
+            SchemaChangeKind::Added => '+',
+            SchemaChangeKind::Removed => '-',
+            SchemaChangeKind::TypeChanged => '~',
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+
+/// The kinds of Meta JSON the search index reads text from.
+#[derive(Serialize, Deserialize, ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TextKind {
+    Message,
+    Post,
+    Comment,
+}
+impl TextKind {
+    /// Recognizes the kind of a JSON file from its path inside the export.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        if !path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        {
+            return None;
+        }
+        let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+        let path = path.to_string_lossy().replace('\\', "/").to_lowercase();
+        if path.contains("messages/") && file_name.starts_with("message_") {
+            Some(TextKind::Message)
+        } else if file_name.starts_with("comments") {
+            Some(TextKind::Comment)
+        } else if path.contains("posts/") {
+            Some(TextKind::Post)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextKind::Message => "message",
+            TextKind::Post => "post",
+            TextKind::Comment => "comment",
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::meta::records::record_arrays;
+use crate::meta::text_kind::TextKind;
+use serde_json::Value;
+
+/// Keys whose string values hold text a person wrote.
+const TEXT_KEYS: &[&str] = &["content", "post", "comment", "description", "text"];
+
+/// A piece of searchable text from a Meta JSON record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextRecord {
+    pub kind: TextKind,
+    /// Conversation title for messages, or the record title for posts and comments
+    pub thread: Option<String>,
+    pub sender: Option<String>,
+    pub timestamp_ms: Option<i64>,
+    pub text: String,
+}
+impl TextRecord {
+    /// Extracts the text records of a parsed file. Strings should already have had their mojibake repaired.
+    pub fn extract(kind: TextKind, value: &Value) -> Vec<Self> {
+        let file_title = value.get("title").and_then(Value::as_str);
+        let mut rtn = Vec::new();
+        for (_, records) in record_arrays(value) {
+            for record in records.iter().filter(|record| record.is_object()) {
+                let mut texts = Vec::new();
+                collect_text(record, &mut texts);
+                if texts.is_empty() {
+                    continue;
+                }
+                let thread = match kind {
+                    TextKind::Message => file_title,
+                    TextKind::Post | TextKind::Comment => {
+                        record.get("title").and_then(Value::as_str)
+                    }
+                };
+                let sender = record
+                    .get("sender_name")
+                    .and_then(Value::as_str)
+                    .or_else(|| find_string(record, "author"));
+                let timestamp_ms =
+                    record
+                        .get("timestamp_ms")
+                        .and_then(Value::as_i64)
+                        .or_else(|| {
+                            record
+                                .get("timestamp")
+                                .and_then(Value::as_i64)
+                                .map(|seconds| seconds * 1000)
+                        });
+                rtn.push(TextRecord {
+                    kind,
+                    thread: thread.map(str::to_string),
+                    sender: sender.map(str::to_string),
+                    timestamp_ms,
+                    text: texts.join("\n"),
+                });
+            }
+        }
+        rtn
+    }
+}
+
+fn collect_text(value: &Value, texts: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map {
+                match item {
+                    Value::String(text) if TEXT_KEYS.contains(&key.as_str()) => {
+                        if !text.trim().is_empty() {
+                            texts.push(text.clone());
+                        }
+                    }
+                    _ => collect_text(item, texts),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_text(item, texts)),
+        _ => {}
+    }
+}
+
+fn find_string<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .and_then(Value::as_str)
+            .or_else(|| map.values().find_map(|item| find_string(item, key))),
+        Value::Array(items) => items.iter().find_map(|item| find_string(item, key)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TextRecord;
+    use crate::meta::text_kind::TextKind;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn extracts_messages() {
+        let thread = json!({
+            "participants": [{"name": "Alice"}, {"name": "Bob"}],
+            "messages": [
+                {"sender_name": "Alice", "timestamp_ms": 1500000000000i64, "content": "See you at 5"},
+                {"sender_name": "Bob", "timestamp_ms": 1500000001000i64, "photos": [{"uri": "x.jpg"}]}
+            ],
+            "title": "Alice and Bob"
+        });
+        let records = TextRecord::extract(TextKind::Message, &thread);
+        assert_eq!(
+            records,
+            vec![TextRecord {
+                kind: TextKind::Message,
+                thread: Some("Alice and Bob".to_string()),
+                sender: Some("Alice".to_string()),
+                timestamp_ms: Some(1500000000000),
+                text: "See you at 5".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn extracts_comments() {
+        let comments = json!({
+            "comments_v2": [{
+                "timestamp": 1500000000,
+                "data": [{"comment": {"timestamp": 1500000000, "comment": "Nice!", "author": "Alice"}}],
+                "title": "Alice commented on Bob's post."
+            }]
+        });
+        let records = TextRecord::extract(TextKind::Comment, &comments);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].sender.as_deref(), Some("Alice"));
+        assert_eq!(records[0].timestamp_ms, Some(1500000000000));
+        assert_eq!(records[0].text, "Nice!");
+    }
+
+    #[test]
+    fn recognizes_paths() {
+        assert_eq!(
+            TextKind::from_path(Path::new(
+                "your_facebook_activity/messages/inbox/bob_123/message_1.json"
+            )),
+            Some(TextKind::Message)
+        );
+        assert_eq!(
+            TextKind::from_path(Path::new(
+                "your_facebook_activity/comments_and_reactions/comments.json"
+            )),
+            Some(TextKind::Comment)
+        );
+        assert_eq!(
+            TextKind::from_path(Path::new(
+                "your_facebook_activity/posts/your_posts__check_ins__photos_and_videos_1.json"
+            )),
+            Some(TextKind::Post)
+        );
+        assert_eq!(
+            TextKind::from_path(Path::new("your_facebook_activity/posts/media/x.jpg")),
+            None
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use serde_json::Value;
+
+/// Calls `visit` with every `uri` string that refers to a file in the export, replacing it when `visit` returns a new value.
+/// Web links are skipped. Returns the number of uris replaced.
+pub fn visit_uris(value: &mut Value, visit: &mut impl FnMut(&str) -> Option<String>) -> usize {
+    match value {
+        Value::Object(map) => {
+            let mut replaced = 0;
+            for (key, item) in map.iter_mut() {
+                match item {
+                    Value::String(uri) if key == "uri" && !is_web_link(uri) => {
+                        if let Some(new_uri) = visit(uri) {
+                            *uri = new_uri;
+                            replaced += 1;
+                        }
+                    }
+                    _ => replaced += visit_uris(item, visit),
+                }
+            }
+            replaced
+        }
+        Value::Array(items) => items.iter_mut().map(|item| visit_uris(item, visit)).sum(),
+        _ => 0,
+    }
+}
+
+/// Whether a `uri` points at the web rather than a file in the export.
+pub fn is_web_link(uri: &str) -> bool {
+    uri.contains("://")
+}
+
+#[cfg(test)]
+mod test {
+    use super::visit_uris;
+    use serde_json::json;
+
+    #[test]
+    fn visits_nested_uris() {
+        let mut value = json!({
+            "messages": [
+                {"photos": [{"uri": "messages/inbox/bob_1/photos/1.jpg"}]},
+                {"share": {"link": "https://example.com"}},
+                {"sticker": {"uri": "https://example.com/sticker.png"}}
+            ],
+            "image": {"uri": "messages/inbox/bob_1/thread.png"}
+        });
+        let mut seen = Vec::new();
+        let replaced = visit_uris(&mut value, &mut |uri| {
+            seen.push(uri.to_string());
+            uri.ends_with(".png").then(|| "moved.png".to_string())
+        });
+        assert_eq!(
+            seen,
+            vec![
+                "messages/inbox/bob_1/photos/1.jpg",
+                "messages/inbox/bob_1/thread.png"
+            ]
+        );
+        assert_eq!(replaced, 1);
+        assert_eq!(value["image"]["uri"], "moved.png");
+    }
+}
@@ -0,0 +1,86 @@
+use crate::sidecar::read_sidecar_json;
+use crate::sidecar::sidecar_dir;
+use crate::sidecar::write_sidecar_json;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Records which on-disk names were produced by a [`crate::name_sanitizer::NameSanitizer`],
+/// keyed by the sanitized path relative to the destination and valued by the unsanitized splat path.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct NameMapping {
+    pub sanitized_to_original: BTreeMap<PathBuf, PathBuf>,
+}
+impl NameMapping {
+    pub fn path(destination: &Path) -> PathBuf {
+        sidecar_dir(destination).join("name_mapping.json")
+    }
+    pub async fn load(destination: &Path) -> eyre::Result<Self> {
+        read_sidecar_json(&Self::path(destination)).await
+    }
+    pub async fn save(&self, destination: &Path) -> eyre::Result<()> {
+        write_sidecar_json(&Self::path(destination), self).await
+    }
+    /// Returns the unsanitized relative path for a relative path found on disk.
+    pub fn original<'a>(&'a self, sanitized: &'a Path) -> &'a Path {
+        self.sanitized_to_original
+            .get(sanitized)
+            .map(PathBuf::as_path)
+            .unwrap_or(sanitized)
+    }
+    /// Records a sanitized name, returning whether it was not already known.
+    pub fn record(&mut self, sanitized: PathBuf, original: PathBuf) -> bool {
+        if sanitized == original {
+            return false;
+        }
+        self.sanitized_to_original
+            .insert(sanitized, original.clone())
+            != Some(original)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NameMapping;
+    use crate::name_sanitizer::NameSanitizer;
+    use crate::windows_name_sanitizer::WindowsNameSanitizer;
+    use std::path::Component;
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    #[test]
+    fn recorded_names_round_trip_through_the_sanitizer() -> eyre::Result<()> {
+        let sanitizer = WindowsNameSanitizer;
+        let mut mapping = NameMapping::default();
+        for original in [
+            "messages/inbox/what?_123/photos/a:b.jpg",
+            "posts/CON/album.",
+            "photos/plain.jpg",
+        ] {
+            let original = PathBuf::from(original);
+            mapping.record(sanitizer.sanitize_path(&original), original);
+        }
+        assert_eq!(mapping.sanitized_to_original.len(), 2);
+
+        let json = serde_json::to_string(&mapping)?;
+        let mapping: NameMapping = serde_json::from_str(&json)?;
+        for (sanitized, original) in &mapping.sanitized_to_original {
+            let unsanitized = sanitized
+                .components()
+                .map(|component| match component {
+                    Component::Normal(name) => {
+                        PathBuf::from(sanitizer.unsanitize_component(&name.to_string_lossy()))
+                    }
+                    other => PathBuf::from(other.as_os_str()),
+                })
+                .collect::<PathBuf>();
+            assert_eq!(&unsanitized, original);
+            assert_eq!(mapping.original(sanitized), original.as_path());
+        }
+        let plain = Path::new("photos/plain.jpg");
+        assert_eq!(mapping.original(plain), plain);
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A stage that turns entry names into names that can be created on the destination filesystem.
+///
+/// Sanitized names that differ from the original are recorded in [`crate::name_mapping::NameMapping`]
+/// so that files on disk can be mapped back to the [`crate::path_inside_zip::PathInsideZip`] they came from.
+pub trait NameSanitizer: Send + Sync {
+    /// Returns a version of a single path component that is safe to create on the destination.
+    fn sanitize_component(&self, component: &str) -> String;
+    /// Reverses [`NameSanitizer::sanitize_component`] for names that did not have to be shortened.
+    fn unsanitize_component(&self, component: &str) -> String;
+    /// Sanitizes every normal component of a relative path.
+    fn sanitize_path(&self, path: &Path) -> PathBuf {
+        path.components()
+            .map(|component| match component {
+                Component::Normal(name) => {
+                    PathBuf::from(self.sanitize_component(&name.to_string_lossy()))
+                }
+                other => PathBuf::from(other.as_os_str()),
+            })
+            .collect()
+    }
+}
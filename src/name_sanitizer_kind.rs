@@ -0,0 +1,24 @@
+use crate::name_sanitizer::NameSanitizer;
+use crate::noop_name_sanitizer::NoopNameSanitizer;
+use crate::windows_name_sanitizer::WindowsNameSanitizer;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Which [`NameSanitizer`] a profile uses when writing to its destination.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NameSanitizerKind {
+    /// Write names exactly as they appear in the zips
+    #[default]
+    None,
+    /// Escape names that are invalid or reserved on NTFS and exFAT
+    Windows,
+}
+impl NameSanitizerKind {
+    pub fn sanitizer(self) -> &'static dyn NameSanitizer {
+        match self {
+            NameSanitizerKind::None => &NoopNameSanitizer,
+            NameSanitizerKind::Windows => &WindowsNameSanitizer,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::name_sanitizer::NameSanitizer;
+
+/// Leaves names untouched, for destinations that accept anything a zip can contain.
+pub struct NoopNameSanitizer;
+
+impl NameSanitizer for NoopNameSanitizer {
+    fn sanitize_component(&self, component: &str) -> String {
+        component.to_string()
+    }
+    fn unsanitize_component(&self, component: &str) -> String {
+        component.to_string()
+    }
+}
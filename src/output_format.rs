@@ -0,0 +1,30 @@
+use clap::ValueEnum;
+use std::fmt::Display;
+
+/// How a report command prints its results.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human readable table
+    #[default]
+    Table,
+    /// Pretty printed JSON
+    Json,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+/// Formats one CSV row, quoting fields that contain separators, quotes or newlines.
+pub fn csv_row(fields: impl IntoIterator<Item = impl Display>) -> String {
+    fields
+        .into_iter()
+        .map(|field| {
+            let field = field.to_string();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
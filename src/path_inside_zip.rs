@@ -1,23 +1,114 @@
 use crate::size_of_thing::KnownCount;
 use crate::size_of_thing::KnownSize;
-use holda::Holda;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::ops::Deref;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
 
-#[derive(Holda)]
-#[holda(NoDisplay)]
+/// A path of an entry inside a zip.
+///
+/// The raw name is kept as found, while hashing, equality and ordering use a canonical form with forward slashes and NFC Unicode normalization,
+/// so `a\b.txt` and `a/b.txt`, or NFD names from zips created on macOS, refer to the same entry.
+#[derive(Clone)]
 pub struct PathInsideZip {
-    inner: Arc<PathBuf>,
+    raw: Arc<PathBuf>,
+    canonical: Arc<PathBuf>,
+}
+impl PathInsideZip {
+    pub fn new(raw: impl Into<Arc<PathBuf>>) -> Self {
+        let raw = raw.into();
+        let canonical = Arc::new(canonicalize(&raw));
+        Self { raw, canonical }
+    }
+    /// The path as it was found in the zip or on disk.
+    pub fn raw(&self) -> &Path {
+        &self.raw
+    }
+    /// The normalized path used for comparisons.
+    pub fn canonical(&self) -> &Path {
+        &self.canonical
+    }
+    /// Key that is equal for paths which would collide on a case-insensitive filesystem such as NTFS or APFS.
+    pub fn case_folded(&self) -> String {
+        self.canonical.to_string_lossy().to_lowercase()
+    }
+}
+
+fn canonicalize(raw: &Path) -> PathBuf {
+    let normalized = raw
+        .to_string_lossy()
+        .replace('\\', "/")
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+        .nfc()
+        .collect::<String>();
+    PathBuf::from(normalized)
+}
+
+impl From<Arc<PathBuf>> for PathInsideZip {
+    fn from(raw: Arc<PathBuf>) -> Self {
+        Self::new(raw)
+    }
+}
+impl From<PathBuf> for PathInsideZip {
+    fn from(raw: PathBuf) -> Self {
+        Self::new(raw)
+    }
+}
+impl Deref for PathInsideZip {
+    type Target = PathBuf;
+    fn deref(&self) -> &Self::Target {
+        &self.canonical
+    }
 }
 impl AsRef<Path> for PathInsideZip {
     fn as_ref(&self) -> &Path {
-        self.inner.as_ref()
+        self.canonical.as_ref()
+    }
+}
+impl PartialEq for PathInsideZip {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical == other.canonical
+    }
+}
+impl Eq for PathInsideZip {}
+impl Hash for PathInsideZip {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical.hash(state);
+    }
+}
+impl PartialOrd for PathInsideZip {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for PathInsideZip {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.canonical.cmp(&other.canonical)
+    }
+}
+impl std::fmt::Debug for PathInsideZip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.raw == self.canonical {
+            f.debug_tuple("PathInsideZip")
+                .field(&self.canonical)
+                .finish()
+        } else {
+            f.debug_struct("PathInsideZip")
+                .field("canonical", &self.canonical)
+                .field("raw", &self.raw)
+                .finish()
+        }
     }
 }
 impl KnownSize for PathInsideZip {
     fn size_in_bytes(&self) -> usize {
-        self.inner.size_in_bytes()
+        self.raw.size_in_bytes() + self.canonical.size_in_bytes()
     }
 }
 impl KnownCount for PathInsideZip {
@@ -39,4 +130,22 @@ mod test {
         assert_eq!(left, right);
         Ok(())
     }
+
+    #[test]
+    fn unicode_is_normalized() -> eyre::Result<()> {
+        let nfd = PathInsideZip::from(PathBuf::from("photos/cafe\u{301}.jpg"));
+        let nfc = PathInsideZip::from(PathBuf::from("photos/caf\u{e9}.jpg"));
+        assert_eq!(nfd, nfc);
+        assert_ne!(nfd.raw(), nfc.raw());
+        Ok(())
+    }
+
+    #[test]
+    fn case_is_preserved_but_foldable() -> eyre::Result<()> {
+        let lower = PathInsideZip::from(PathBuf::from("a/photo.jpg"));
+        let upper = PathInsideZip::from(PathBuf::from("A/Photo.JPG"));
+        assert_ne!(lower, upper);
+        assert_eq!(lower.case_folded(), upper.case_folded());
+        Ok(())
+    }
 }
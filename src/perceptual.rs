@@ -0,0 +1,66 @@
+use crate::zip_entry::ZipEntry;
+use image::load_from_memory;
+use img_hash::HashAlg;
+use img_hash::HasherConfig;
+use img_hash::ImageHash;
+use itertools::Itertools;
+use std::path::Path;
+
+/// Extensions of entries we compare by perceptual hash when their CRC32 differs.
+pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "bmp", "tiff", "webp", "heic"];
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        IMAGE_EXTENSIONS
+            .iter()
+            .any(|image_ext| ext.eq_ignore_ascii_case(image_ext))
+    })
+}
+
+/// Largest perceptual hash distance at which sync treats image variants of one name as the same picture.
+pub const SYNC_SIMILARITY_THRESHOLD: u32 = 5;
+
+/// Whether sync compares the variants of an entry by perceptual hash.
+///
+/// Unlike [`is_image`], the extension must match [`IMAGE_EXTENSIONS`] exactly, so `PHOTO.JPG` variants are never merged.
+pub fn sync_compares_perceptually(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext))
+}
+
+pub fn image_hasher_config() -> HasherConfig {
+    HasherConfig::new().hash_alg(HashAlg::Gradient)
+}
+
+/// Returns the perceptual hash of an encoded image, or `None` if it cannot be decoded.
+pub fn hash_image_bytes(data: &[u8], hasher_config: &HasherConfig) -> Option<ImageHash> {
+    let image = load_from_memory(data).ok()?;
+    Some(hasher_config.to_hasher().hash_image(&image))
+}
+
+/// Hashes every entry, returning `None` if any of them cannot be decoded as an image.
+pub async fn hash_images(
+    entries: &[ZipEntry],
+    hasher_config: &HasherConfig,
+) -> eyre::Result<Option<Vec<ImageHash>>> {
+    let mut hashes = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let data = entry.bytes().await?;
+        match hash_image_bytes(&data, hasher_config) {
+            Some(hash) => hashes.push(hash),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(hashes))
+}
+
+/// Largest Hamming distance between any two of the hashes.
+pub fn max_distance(hashes: &[ImageHash]) -> u32 {
+    hashes
+        .iter()
+        .tuple_combinations()
+        .map(|(a, b)| a.dist(b))
+        .max()
+        .unwrap_or(0)
+}
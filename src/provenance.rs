@@ -0,0 +1,72 @@
+use crate::provenance_record::ProvenanceRecord;
+use crate::sidecar::read_sidecar_json;
+use crate::sidecar::sidecar_dir;
+use crate::sidecar::write_sidecar_json;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Provenance of every file extracted to an output directory, keyed by the extracted path relative to it.
+///
+/// It is kept in the sidecar directory, so it cannot collide with an extracted `provenance.json`,
+/// and records from earlier extractions into the same directory are kept.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Provenance {
+    pub files: BTreeMap<PathBuf, ProvenanceRecord>,
+}
+impl Provenance {
+    pub fn path(output_dir: &Path) -> PathBuf {
+        sidecar_dir(output_dir).join("provenance.json")
+    }
+    /// Loads the records of earlier extractions, failing rather than discarding a file that cannot be parsed.
+    pub async fn load(output_dir: &Path) -> eyre::Result<Self> {
+        read_sidecar_json(&Self::path(output_dir)).await
+    }
+    pub async fn save(&self, output_dir: &Path) -> eyre::Result<()> {
+        write_sidecar_json(&Self::path(output_dir), self).await
+    }
+    /// Adds a record, replacing an earlier one for the same extracted file.
+    pub fn record(&mut self, record: ProvenanceRecord) {
+        self.files.insert(record.extracted.clone(), record);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Provenance;
+    use crate::provenance_record::ProvenanceRecord;
+    use chrono::NaiveDate;
+    use chrono::Utc;
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    fn record(extracted: &str, crc32: u32) -> ProvenanceRecord {
+        ProvenanceRecord {
+            extracted: PathBuf::from(extracted),
+            path_inside_zip: PathBuf::from(extracted),
+            zip: PathBuf::from("export-1.zip"),
+            export_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            crc32,
+            modified: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn later_records_replace_earlier_ones_for_the_same_file() {
+        let mut provenance = Provenance::default();
+        provenance.record(record("photos/a.jpg", 1));
+        provenance.record(record("photos/b.jpg", 2));
+        provenance.record(record("photos/a.jpg", 3));
+        assert_eq!(provenance.files.len(), 2);
+        assert_eq!(provenance.files[Path::new("photos/a.jpg")].crc32, 3);
+    }
+
+    #[test]
+    fn is_not_kept_where_an_extracted_file_could_be() {
+        let output = Path::new("out");
+        assert_ne!(Provenance::path(output), output.join("provenance.json"));
+        assert!(Provenance::path(output).starts_with(output.join(".thrumzip")));
+    }
+}
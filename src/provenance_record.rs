@@ -0,0 +1,18 @@
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Where an extracted file came from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProvenanceRecord {
+    /// Path of the extracted file, relative to the output directory
+    pub extracted: PathBuf,
+    pub path_inside_zip: PathBuf,
+    pub zip: PathBuf,
+    pub export_date: NaiveDate,
+    pub crc32: u32,
+    pub modified: DateTime<Utc>,
+}
@@ -0,0 +1,9 @@
+use crate::skipped_entry::SkippedEntry;
+use crate::zip_entry::ZipEntry;
+
+/// File entries read from the source zips, along with everything that had to be skipped.
+#[derive(Debug, Default)]
+pub struct ReadEntries {
+    pub entries: Vec<ZipEntry>,
+    pub skipped: Vec<SkippedEntry>,
+}
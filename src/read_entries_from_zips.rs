@@ -1,8 +1,13 @@
 use crate::path_inside_zip::PathInsideZip;
 use crate::path_to_zip::PathToZip;
+use crate::read_entries::ReadEntries;
+use crate::recover_entries::scan_local_file_headers;
+use crate::skipped_entry::SkippedEntry;
+use crate::unsafe_names::quarantine_path;
 use crate::zip_entry::ZipEntry;
-use eyre::bail;
+use eyre::Context;
 use positioned_io::RandomAccessFile;
+use rc_zip::parse::Entry;
 use rc_zip_tokio::ReadZip;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -11,47 +16,128 @@ use tracing::info;
 use tracing::warn;
 
 /// Returns file entries from inside provided zip files.
-pub async fn read_entries_from_zips(zips: Vec<PathToZip>) -> eyre::Result<Vec<ZipEntry>> {
+///
+/// A zip that cannot be read, or an entry that cannot be used, is recorded in [`ReadEntries::skipped`] instead of failing the whole run.
+/// When `recover` is set, zips without a readable central directory are scanned for local file headers instead of being skipped.
+pub async fn read_entries_from_zips(
+    zips: Vec<PathToZip>,
+    recover: bool,
+) -> eyre::Result<ReadEntries> {
     info!("Reading entries from {} zips", zips.len());
     if zips.is_empty() {
         warn!("No zips provided, returning empty defaults.");
         return Ok(Default::default());
     }
 
-    let mut tasks: JoinSet<Result<Vec<ZipEntry>, eyre::Error>> = JoinSet::new();
+    let mut tasks: JoinSet<(PathToZip, eyre::Result<ReadEntries>)> = JoinSet::new();
     for path_to_zip in zips {
-        tasks.spawn(get_entries_from_zip(path_to_zip));
+        tasks.spawn(async move {
+            let result = get_entries_from_zip(path_to_zip.clone(), recover).await;
+            (path_to_zip, result)
+        });
     }
 
-    let mut rtn = Vec::with_capacity(tasks.len());
+    let mut rtn = ReadEntries::default();
     while let Some(res) = tasks.join_next().await {
-        let zip_entries = res??;
-        rtn.extend(zip_entries);
+        let (path_to_zip, result) = res?;
+        match result {
+            Ok(zip_entries) => {
+                rtn.entries.extend(zip_entries.entries);
+                rtn.skipped.extend(zip_entries.skipped);
+            }
+            Err(e) => {
+                warn!("Skipping zip {}: {e:#}", path_to_zip.display());
+                rtn.skipped.push(SkippedEntry {
+                    path_to_zip,
+                    entry_name: None,
+                    reason: format!("{e:#}"),
+                });
+            }
+        }
     }
-    rtn.retain(|e| e.is_file());
+    rtn.entries.retain(|e| e.is_file());
     Ok(rtn)
 }
 
-async fn get_entries_from_zip(path_to_zip: PathToZip) -> eyre::Result<Vec<ZipEntry>> {
+async fn get_entries_from_zip(path_to_zip: PathToZip, recover: bool) -> eyre::Result<ReadEntries> {
+    let file_len = tokio::fs::metadata(&path_to_zip)
+        .await
+        .wrap_err("Failed to read zip metadata")?
+        .len();
     let file = Arc::new(RandomAccessFile::open(path_to_zip.clone())?);
-    let archive = file.read_zip().await?;
-    let entries = archive.into_entries();
-    let mut rtn = Vec::with_capacity(entries.len());
-    for entry in entries {
-        let Some(path_inside_zip) = entry.sanitized_name() else {
-            bail!(
-                "Entry {:?} in zip {} has no sanitized name, cannot process it.",
-                entry.name,
+    let mut rtn = ReadEntries::default();
+    let entries: Vec<Entry> = match file.read_zip().await {
+        Ok(archive) => archive.into_entries(),
+        Err(e) if recover => {
+            warn!(
+                "Failed to read central directory of {} ({e}), scanning local file headers instead",
+                path_to_zip.display()
+            );
+            let scan_file = file.clone();
+            let recovered =
+                tokio::task::spawn_blocking(move || scan_local_file_headers(&scan_file, file_len))
+                    .await?;
+            // Without the central directory there is no way to know which entries the scan missed
+            rtn.skipped.push(SkippedEntry {
+                path_to_zip: path_to_zip.clone(),
+                entry_name: None,
+                reason: format!(
+                    "Central directory could not be read ({e}), entries were recovered from local file headers"
+                ),
+            });
+            let mut entries = Vec::with_capacity(recovered.len());
+            for entry in recovered {
+                match entry {
+                    Ok(entry) => entries.push(entry),
+                    Err((entry_name, reason)) => rtn.skipped.push(SkippedEntry {
+                        path_to_zip: path_to_zip.clone(),
+                        entry_name,
+                        reason,
+                    }),
+                }
+            }
+            info!(
+                "Recovered {} entries from {}",
+                entries.len(),
                 path_to_zip.display()
             );
+            entries
+        }
+        Err(e) => {
+            return Err(e).wrap_err("Failed to read central directory (try --recover)");
+        }
+    };
+
+    rtn.entries.reserve(entries.len());
+    for entry in entries {
+        let path_inside_zip = match entry.sanitized_name() {
+            Some(sanitized_name) => PathBuf::from(sanitized_name),
+            None => {
+                let quarantined = quarantine_path(&entry.name);
+                warn!(
+                    "Entry {:?} in zip {} has no sanitized name, quarantining it as {}",
+                    entry.name,
+                    path_to_zip.display(),
+                    quarantined.display()
+                );
+                quarantined
+            }
         };
+        if entry.header_offset + entry.compressed_size > file_len {
+            rtn.skipped.push(SkippedEntry {
+                path_to_zip: path_to_zip.clone(),
+                entry_name: Some(entry.name.clone()),
+                reason: "Entry data is past the end of the file, the zip is truncated".to_string(),
+            });
+            continue;
+        }
         let zip_entry = ZipEntry {
             path_to_zip: path_to_zip.clone(),
-            path_inside_zip: PathInsideZip::new(PathBuf::from(path_inside_zip)),
+            path_inside_zip: PathInsideZip::new(path_inside_zip),
             file: file.clone(),
             entry,
         };
-        rtn.push(zip_entry);
+        rtn.entries.push(zip_entry);
     }
 
     Ok(rtn)
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+/// An entry of the original zip that the rebuilt zip does not reproduce byte-exactly.
+#[derive(Serialize, Debug, Clone)]
+pub struct RebuildIssue {
+    /// The raw entry name from the original central directory
+    pub entry_name: String,
+    pub reason: String,
+}
@@ -0,0 +1,18 @@
+use crate::rebuild_issue::RebuildIssue;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Outcome of reconstructing a source zip from the destination.
+#[derive(Serialize, Debug, Clone)]
+pub struct RebuildReport {
+    pub zip: PathBuf,
+    pub rebuilt: PathBuf,
+    /// Entries whose CRC32 in the rebuilt zip matches the original central directory
+    pub exact: usize,
+    pub issues: Vec<RebuildIssue>,
+}
+impl RebuildReport {
+    pub fn is_exact(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+/// How a record went missing from the newest export.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordLoss {
+    /// The file is still exported but the record is gone, such as a deleted post
+    Deleted,
+    /// The record is still exported but its content was removed
+    Unsent,
+    /// The whole file or thread is no longer exported
+    FileMissing,
+}
+impl RecordLoss {
+    /// Classifies a record by the last export it was seen in, oldest export first. Returns `None` if nothing was lost.
+    ///
+    /// `file_in_newest` says whether the record's file or thread is in the newest export,
+    /// and `had_content` whether any export showed the record before it was unsent.
+    pub fn classify(
+        last_seen: usize,
+        newest: usize,
+        file_in_newest: bool,
+        unsent: bool,
+        had_content: bool,
+    ) -> Option<Self> {
+        if last_seen < newest {
+            Some(if file_in_newest {
+                RecordLoss::Deleted
+            } else {
+                RecordLoss::FileMissing
+            })
+        } else if unsent && had_content {
+            Some(RecordLoss::Unsent)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RecordLoss;
+
+    #[test]
+    fn classifies_losses() {
+        assert_eq!(
+            RecordLoss::classify(0, 2, true, false, true),
+            Some(RecordLoss::Deleted)
+        );
+        assert_eq!(
+            RecordLoss::classify(1, 2, false, false, true),
+            Some(RecordLoss::FileMissing)
+        );
+        assert_eq!(
+            RecordLoss::classify(2, 2, true, true, true),
+            Some(RecordLoss::Unsent)
+        );
+        assert_eq!(RecordLoss::classify(2, 2, true, false, true), None);
+    }
+
+    #[test]
+    fn records_unsent_before_the_oldest_export_are_not_lost() {
+        assert_eq!(RecordLoss::classify(2, 2, true, true, false), None);
+        // Gone from the newest export counts as a loss even if it was already unsent
+        assert_eq!(
+            RecordLoss::classify(1, 2, true, true, false),
+            Some(RecordLoss::Deleted)
+        );
+    }
+}
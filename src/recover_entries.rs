@@ -0,0 +1,373 @@
+use chrono::DateTime;
+use chrono::Utc;
+use positioned_io::ReadAt;
+use rc_zip::parse::Entry;
+use rc_zip::parse::HostSystem;
+use rc_zip::parse::Method;
+use rc_zip::parse::Mode;
+use rc_zip::parse::MsdosTimestamp;
+use rc_zip::parse::Version;
+
+const LOCAL_FILE_HEADER_SIGNATURE: [u8; 4] = *b"PK\x03\x04";
+const DATA_DESCRIPTOR_SIGNATURE: [u8; 4] = *b"PK\x07\x08";
+const CENTRAL_DIRECTORY_SIGNATURE: [u8; 4] = *b"PK\x01\x02";
+const LOCAL_FILE_HEADER_LEN: usize = 30;
+const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+const SCAN_CHUNK_LEN: usize = 1024 * 1024;
+
+/// An entry found by walking local file headers, or the reason one could not be used.
+pub type RecoveredEntry = Result<Entry, (Option<String>, String)>;
+
+/// Walks the local file headers from the start of a zip whose end of central directory record is missing or unreadable.
+///
+/// This is the fallback for partially downloaded exports: every entry whose header and data are fully present is returned,
+/// and the scan stops at the first header that runs past the end of the file.
+pub fn scan_local_file_headers(file: &impl ReadAt, file_len: u64) -> Vec<RecoveredEntry> {
+    let mut rtn = Vec::new();
+    let mut offset = 0u64;
+    while offset + LOCAL_FILE_HEADER_LEN as u64 <= file_len {
+        let mut header = [0u8; LOCAL_FILE_HEADER_LEN];
+        if let Err(e) = file.read_exact_at(offset, &mut header) {
+            rtn.push(Err((
+                None,
+                format!("Failed to read header at {offset}: {e}"),
+            )));
+            break;
+        }
+        if header[0..4] != LOCAL_FILE_HEADER_SIGNATURE {
+            // Either the central directory starts here or the data is garbage; look for the next header.
+            match find_signature(file, file_len, offset + 1, &[LOCAL_FILE_HEADER_SIGNATURE]) {
+                Some(next) => {
+                    offset = next;
+                    continue;
+                }
+                None => break,
+            }
+        }
+
+        let reader_version = u16_at(&header, 4);
+        let flags = u16_at(&header, 6);
+        let method = u16_at(&header, 8);
+        let timestamp = MsdosTimestamp {
+            time: u16_at(&header, 10),
+            date: u16_at(&header, 12),
+        };
+        let mut crc32 = u32_at(&header, 14);
+        let mut compressed_size = u32_at(&header, 18) as u64;
+        let mut uncompressed_size = u32_at(&header, 22) as u64;
+        let name_len = u16_at(&header, 26) as usize;
+        let extra_len = u16_at(&header, 28) as usize;
+
+        let mut name_and_extra = vec![0u8; name_len + extra_len];
+        if let Err(e) =
+            file.read_exact_at(offset + LOCAL_FILE_HEADER_LEN as u64, &mut name_and_extra)
+        {
+            rtn.push(Err((None, format!("Truncated header at {offset}: {e}"))));
+            break;
+        }
+        let name = String::from_utf8_lossy(&name_and_extra[..name_len]).into_owned();
+        let extra = &name_and_extra[name_len..];
+        if let Some((zip64_uncompressed, zip64_compressed)) = zip64_sizes(extra) {
+            if uncompressed_size == u32::MAX as u64 {
+                uncompressed_size = zip64_uncompressed;
+            }
+            if compressed_size == u32::MAX as u64 {
+                compressed_size = zip64_compressed;
+            }
+        }
+
+        let data_start = offset + (LOCAL_FILE_HEADER_LEN + name_len + extra_len) as u64;
+        let mut next_offset = data_start + compressed_size;
+        if flags & FLAG_DATA_DESCRIPTOR != 0 && compressed_size == 0 {
+            // Sizes live in a data descriptor after the data, so we have to find it.
+            let Some(descriptor) = find_data_descriptor(file, file_len, data_start) else {
+                rtn.push(Err((Some(name), "Missing data descriptor".to_string())));
+                break;
+            };
+            crc32 = descriptor.crc32;
+            compressed_size = descriptor.compressed_size;
+            uncompressed_size = descriptor.uncompressed_size;
+            next_offset = descriptor.end;
+        }
+
+        if data_start + compressed_size > file_len {
+            rtn.push(Err((
+                Some(name),
+                format!(
+                    "Entry data ends at {} but the file is only {} bytes",
+                    data_start + compressed_size,
+                    file_len
+                ),
+            )));
+            break;
+        }
+
+        rtn.push(Ok(Entry {
+            name,
+            method: Method::from(method),
+            comment: String::new(),
+            modified: timestamp
+                .to_datetime()
+                .unwrap_or(DateTime::<Utc>::UNIX_EPOCH),
+            created: None,
+            accessed: None,
+            header_offset: offset,
+            reader_version: Version {
+                host_system: HostSystem::from((reader_version >> 8) as u8),
+                version: reader_version as u8,
+            },
+            flags,
+            uid: None,
+            gid: None,
+            crc32,
+            compressed_size,
+            uncompressed_size,
+            mode: Mode(0o644),
+        }));
+        offset = next_offset;
+    }
+    rtn
+}
+
+fn u16_at(buf: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([buf[at], buf[at + 1]])
+}
+
+fn u32_at(buf: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]])
+}
+
+fn u64_at(buf: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(buf[at..at + 8].try_into().unwrap_or_default())
+}
+
+/// Returns the (uncompressed, compressed) sizes from a zip64 extended information extra field.
+fn zip64_sizes(mut extra: &[u8]) -> Option<(u64, u64)> {
+    while extra.len() >= 4 {
+        let id = u16_at(extra, 0);
+        let len = u16_at(extra, 2) as usize;
+        let body = extra.get(4..4 + len)?;
+        if id == ZIP64_EXTRA_FIELD_ID && body.len() >= 16 {
+            let uncompressed = u64::from_le_bytes(body[0..8].try_into().ok()?);
+            let compressed = u64::from_le_bytes(body[8..16].try_into().ok()?);
+            return Some((uncompressed, compressed));
+        }
+        extra = &extra[4 + len..];
+    }
+    None
+}
+
+/// The sizes and checksum written after an entry's data, and the offset just past them.
+struct DataDescriptor {
+    crc32: u32,
+    compressed_size: u64,
+    uncompressed_size: u64,
+    end: u64,
+}
+
+/// Finds the data descriptor of an entry whose data starts at `data_start`.
+///
+/// The descriptor's own signature is optional and its sizes are 64-bit for zip64 entries, so instead of looking for it directly
+/// this looks for the header that follows it, or the end of the file, and accepts the first descriptor layout ending there
+/// whose compressed size matches the distance from the start of the data.
+fn find_data_descriptor(
+    file: &impl ReadAt,
+    file_len: u64,
+    data_start: u64,
+) -> Option<DataDescriptor> {
+    let mut search_from = data_start;
+    loop {
+        let boundary = find_signature(
+            file,
+            file_len,
+            search_from,
+            &[LOCAL_FILE_HEADER_SIGNATURE, CENTRAL_DIRECTORY_SIGNATURE],
+        );
+        if let Some(descriptor) =
+            descriptor_ending_at(file, data_start, boundary.unwrap_or(file_len))
+        {
+            return Some(descriptor);
+        }
+        search_from = boundary? + 1;
+    }
+}
+
+/// Tries each data descriptor layout, with and without signature and with 32 or 64-bit sizes, ending at `end`.
+fn descriptor_ending_at(file: &impl ReadAt, data_start: u64, end: u64) -> Option<DataDescriptor> {
+    for (len, signed, zip64) in [
+        (16, true, false),
+        (12, false, false),
+        (24, true, true),
+        (20, false, true),
+    ] {
+        let Some(start) = end
+            .checked_sub(len as u64)
+            .filter(|start| *start >= data_start)
+        else {
+            continue;
+        };
+        let mut buf = [0u8; 24];
+        let buf = &mut buf[..len];
+        if file.read_exact_at(start, buf).is_err() {
+            continue;
+        }
+        let fields = if signed {
+            if buf[0..4] != DATA_DESCRIPTOR_SIGNATURE {
+                continue;
+            }
+            &buf[4..]
+        } else {
+            &buf[..]
+        };
+        let (compressed_size, uncompressed_size) = if zip64 {
+            (u64_at(fields, 4), u64_at(fields, 12))
+        } else {
+            (u32_at(fields, 4) as u64, u32_at(fields, 8) as u64)
+        };
+        if data_start + compressed_size == start {
+            return Some(DataDescriptor {
+                crc32: u32_at(fields, 0),
+                compressed_size,
+                uncompressed_size,
+                end,
+            });
+        }
+    }
+    None
+}
+
+/// Finds the next occurrence of any of the 4 byte signatures at or after `start`.
+fn find_signature(
+    file: &impl ReadAt,
+    file_len: u64,
+    start: u64,
+    signatures: &[[u8; 4]],
+) -> Option<u64> {
+    let mut buf = vec![0u8; SCAN_CHUNK_LEN];
+    let mut pos = start;
+    while pos < file_len {
+        let len = ((file_len - pos) as usize).min(SCAN_CHUNK_LEN);
+        let chunk = &mut buf[..len];
+        file.read_exact_at(pos, chunk).ok()?;
+        if let Some(found) = chunk
+            .windows(4)
+            .position(|w| signatures.iter().any(|signature| w == signature))
+        {
+            return Some(pos + found as u64);
+        }
+        if len < 4 {
+            return None;
+        }
+        // Overlap chunks so a signature straddling the boundary is not missed.
+        pos += (len - 3) as u64;
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::scan_local_file_headers;
+
+    /// A stored local file entry, with its sizes in a trailing data descriptor when `descriptor` is set,
+    /// which is signed when it holds `true`.
+    fn local_entry(name: &str, data: &[u8], descriptor: Option<bool>) -> Vec<u8> {
+        let crc32 = crc32fast::hash(data);
+        let size = data.len() as u32;
+        let (flags, header_crc32, header_size) = match descriptor {
+            Some(_) => (1u16 << 3, 0, 0),
+            None => (0, crc32, size),
+        };
+        let mut rtn = b"PK\x03\x04".to_vec();
+        rtn.extend(20u16.to_le_bytes());
+        rtn.extend(flags.to_le_bytes());
+        rtn.extend(0u16.to_le_bytes()); // stored
+        rtn.extend([0u8; 4]); // time and date
+        rtn.extend(header_crc32.to_le_bytes());
+        rtn.extend(header_size.to_le_bytes());
+        rtn.extend(header_size.to_le_bytes());
+        rtn.extend((name.len() as u16).to_le_bytes());
+        rtn.extend(0u16.to_le_bytes());
+        rtn.extend(name.as_bytes());
+        rtn.extend(data);
+        if let Some(signed) = descriptor {
+            if signed {
+                rtn.extend(b"PK\x07\x08");
+            }
+            rtn.extend(crc32.to_le_bytes());
+            rtn.extend(size.to_le_bytes());
+            rtn.extend(size.to_le_bytes());
+        }
+        rtn
+    }
+
+    /// The start of a central directory, which the scan stops at.
+    const CENTRAL_DIRECTORY: &[u8] = b"PK\x01\x02 the rest of the central directory";
+
+    fn scan(zip: &[u8]) -> Vec<Result<(String, u64, u32), Option<String>>> {
+        scan_local_file_headers(&zip.to_vec(), zip.len() as u64)
+            .into_iter()
+            .map(|entry| match entry {
+                Ok(entry) => Ok((entry.name, entry.compressed_size, entry.crc32)),
+                Err((name, _reason)) => Err(name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_stored_entries() {
+        let zip = [
+            local_entry("a.txt", b"hello", None),
+            local_entry("b/c.json", b"{}", None),
+            CENTRAL_DIRECTORY.to_vec(),
+        ]
+        .concat();
+        assert_eq!(
+            scan(&zip),
+            vec![
+                Ok(("a.txt".to_string(), 5, crc32fast::hash(b"hello"))),
+                Ok(("b/c.json".to_string(), 2, crc32fast::hash(b"{}"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_sizes_from_data_descriptors() {
+        // The data contains a descriptor signature of its own, which must not be mistaken for the real one
+        let data = b"data with PK\x07\x08 inside";
+        let zip = [
+            local_entry("signed.bin", data, Some(true)),
+            local_entry("unsigned.bin", data, Some(false)),
+            local_entry("after.txt", b"after", None),
+            CENTRAL_DIRECTORY.to_vec(),
+        ]
+        .concat();
+        let len = data.len() as u64;
+        assert_eq!(
+            scan(&zip),
+            vec![
+                Ok(("signed.bin".to_string(), len, crc32fast::hash(data))),
+                Ok(("unsigned.bin".to_string(), len, crc32fast::hash(data))),
+                Ok(("after.txt".to_string(), 5, crc32fast::hash(b"after"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_a_truncated_tail() {
+        let complete = local_entry("complete.txt", b"complete", None);
+        let truncated = local_entry("truncated.txt", b"cut off halfway", None);
+        let zip = [complete, truncated[..truncated.len() - 5].to_vec()].concat();
+        assert_eq!(
+            scan(&zip),
+            vec![
+                Ok(("complete.txt".to_string(), 8, crc32fast::hash(b"complete"))),
+                Err(Some("truncated.txt".to_string())),
+            ]
+        );
+
+        let descriptor = local_entry("descriptor.txt", b"never finished", Some(false));
+        let zip = descriptor[..descriptor.len() - 6].to_vec();
+        assert_eq!(scan(&zip), vec![Err(Some("descriptor.txt".to_string()))]);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::attachment_index::AttachmentIndex;
+use crate::html::escape_html;
+use crate::html::relative_href;
+use crate::media_files::is_video;
+use crate::meta::messages::conversation::Conversation;
+use crate::meta::messages::message::Message;
+use crate::perceptual::is_image;
+use crate::render_state::RenderState;
+use chrono::DateTime;
+use chrono::Local;
+use image::DynamicImage;
+use image::ImageFormat;
+use image::load_from_memory;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::warn;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:0 auto;max-width:1100px;padding:1em;background:#fafafa;color:#222}\
+a{color:#2457a6}\
+.grid{display:flex;flex-wrap:wrap;gap:8px}\
+.tile{width:180px;font-size:.8em;color:#666}\
+.tile img,.tile video{width:180px;height:180px;object-fit:cover;background:#ddd}\
+.message{background:#fff;border-radius:6px;margin:.5em 0;padding:.5em .8em}\
+.meta{font-size:.8em;color:#666}\
+.message img{max-width:240px;max-height:240px}\
+.message video{max-width:360px}\
+.missing{color:#a33}";
+
+/// Every page other than the index is one folder deep.
+const INDEX_LINK: &str = "<p><a href=\"../index.html\">Back to the archive</a></p>";
+
+/// Everything a page needs to turn destination files into links and thumbnails.
+pub struct RenderSite {
+    pub root: PathBuf,
+    /// Where the media comes from, thumbnails mirror its layout
+    pub destination: PathBuf,
+    pub thumbnail_size: u32,
+    pub attachments: AttachmentIndex,
+    pub thumbnail_permits: Arc<Semaphore>,
+}
+
+impl RenderSite {
+    pub async fn media_page(
+        &self,
+        page: &str,
+        title: &str,
+        items: &[(DateTime<Local>, &Path)],
+    ) -> String {
+        let page_dir = self.page_dir(page);
+        let thumbnails = self
+            .thumbnails(
+                items
+                    .iter()
+                    .map(|(_, path)| *path)
+                    .filter(|path| is_image(path)),
+            )
+            .await;
+        let mut body = String::from(INDEX_LINK);
+        body.push_str("<div class=\"grid\">");
+        for (taken, path) in items {
+            let href = escape_html(&relative_href(&page_dir, path));
+            let caption = taken.format("%Y-%m-%d %H:%M");
+            let preview = if is_video(path) {
+                format!("<video controls preload=\"metadata\" src=\"{href}\"></video>")
+            } else if let Some(thumbnail) = thumbnails.get(*path) {
+                format!(
+                    "<a href=\"{href}\"><img loading=\"lazy\" src=\"{}\"></a>",
+                    escape_html(&relative_href(&page_dir, thumbnail))
+                )
+            } else {
+                format!(
+                    "<a href=\"{href}\">{}</a>",
+                    escape_html(&path.file_name().unwrap_or_default().to_string_lossy())
+                )
+            };
+            _ = write!(body, "<div class=\"tile\">{preview}<br>{caption}</div>");
+        }
+        body.push_str("</div>");
+        page_html(title, &body)
+    }
+
+    pub async fn thread_page(
+        &self,
+        page: &str,
+        title: &str,
+        conversation: &Conversation,
+    ) -> String {
+        let page_dir = self.page_dir(page);
+        let images = conversation
+            .messages
+            .iter()
+            .flat_map(|message| message.photos.iter().chain(&message.sticker))
+            .filter_map(|media| self.attachments.resolve(media))
+            .collect_vec();
+        let thumbnails = self.thumbnails(images.into_iter()).await;
+        let mut body = String::from(INDEX_LINK);
+        _ = write!(
+            body,
+            "<p>{}</p>",
+            escape_html(&conversation.senders().join(", "))
+        );
+        for message in &conversation.messages {
+            body.push_str(&self.message_html(message, &page_dir, &thumbnails));
+        }
+        page_html(title, &body)
+    }
+
+    fn message_html(
+        &self,
+        message: &Message,
+        page_dir: &Path,
+        thumbnails: &HashMap<PathBuf, PathBuf>,
+    ) -> String {
+        let sent = DateTime::from_timestamp_millis(message.timestamp_ms)
+            .map(|sent| {
+                sent.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let mut html = format!(
+            "<div class=\"message\"><div class=\"meta\">{} &middot; {sent}{}</div>",
+            escape_html(&message.sender_name),
+            if message.is_unsent {
+                " &middot; unsent"
+            } else {
+                ""
+            }
+        );
+        if let Some(content) = &message.content {
+            _ = write!(
+                html,
+                "<p>{}</p>",
+                escape_html(content).replace('\n', "<br>")
+            );
+        }
+        for media in message.media() {
+            let Some(path) = self.attachments.resolve(media) else {
+                _ = write!(
+                    html,
+                    "<p class=\"missing\">Missing {}</p>",
+                    escape_html(media.file_name())
+                );
+                continue;
+            };
+            let href = escape_html(&relative_href(page_dir, path));
+            let attachment = if message.videos.contains(media) {
+                format!("<video controls preload=\"metadata\" src=\"{href}\"></video>")
+            } else if message.audio_files.contains(media) {
+                format!("<audio controls preload=\"none\" src=\"{href}\"></audio>")
+            } else if message.gifs.contains(media) {
+                format!("<img loading=\"lazy\" src=\"{href}\">")
+            } else if let Some(thumbnail) = thumbnails.get(path) {
+                format!(
+                    "<a href=\"{href}\"><img loading=\"lazy\" src=\"{}\"></a>",
+                    escape_html(&relative_href(page_dir, thumbnail))
+                )
+            } else {
+                format!("<a href=\"{href}\">{}</a>", escape_html(media.file_name()))
+            };
+            _ = write!(html, "<p>{attachment}</p>");
+        }
+        let shared_link = message.share.as_ref().and_then(|share| {
+            let link = share.link.as_ref()?;
+            Some((link, share.share_text.as_deref().unwrap_or(link)))
+        });
+        if let Some((link, text)) = shared_link {
+            _ = write!(
+                html,
+                "<p><a href=\"{}\">{}</a></p>",
+                escape_html(link),
+                escape_html(text)
+            );
+        }
+        if !message.reactions.is_empty() {
+            let reactions = message
+                .reactions
+                .iter()
+                .map(|reaction| format!("{} {}", reaction.reaction, reaction.actor))
+                .join(", ");
+            _ = write!(
+                html,
+                "<div class=\"meta\">{}</div>",
+                escape_html(&reactions)
+            );
+        }
+        html.push_str("</div>");
+        html
+    }
+
+    fn page_dir(&self, page: &str) -> PathBuf {
+        self.root
+            .join(page)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.root.clone())
+    }
+
+    /// Creates missing or outdated thumbnails, returning the thumbnail of every image that could be decoded.
+    async fn thumbnails<'a>(
+        &self,
+        images: impl Iterator<Item = &'a Path>,
+    ) -> HashMap<PathBuf, PathBuf> {
+        let mut tasks = JoinSet::new();
+        for image in images.unique() {
+            let source = image.to_path_buf();
+            // Named after the source path, so two sources never share a thumbnail
+            let Ok(relative) = source.strip_prefix(&self.destination) else {
+                warn!(
+                    "No thumbnail for {}, it is outside the destination",
+                    source.display()
+                );
+                continue;
+            };
+            let mut thumbnail = self.root.join("thumbs").join(relative).into_os_string();
+            thumbnail.push(".jpg");
+            let thumbnail = PathBuf::from(thumbnail);
+            let size = self.thumbnail_size;
+            let permits = self.thumbnail_permits.clone();
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let created = tokio::task::spawn_blocking({
+                    let source = source.clone();
+                    let thumbnail = thumbnail.clone();
+                    move || ensure_thumbnail(&source, &thumbnail, size)
+                })
+                .await;
+                match created {
+                    Ok(Ok(())) => Some((source, thumbnail)),
+                    Ok(Err(e)) => {
+                        warn!("No thumbnail for {}: {e:#}", source.display());
+                        None
+                    }
+                    Err(e) => {
+                        warn!("Thumbnail task for {} failed: {e}", source.display());
+                        None
+                    }
+                }
+            });
+        }
+        let mut rtn = HashMap::new();
+        while let Some(res) = tasks.join_next().await {
+            if let Ok(Some((source, thumbnail))) = res {
+                rtn.insert(source, thumbnail);
+            }
+        }
+        rtn
+    }
+}
+
+/// Writes a thumbnail unless one newer than the source already exists.
+fn ensure_thumbnail(source: &Path, thumbnail: &Path, size: u32) -> eyre::Result<()> {
+    let source_modified = std::fs::metadata(source)?.modified()?;
+    let thumbnail_modified = std::fs::metadata(thumbnail).and_then(|existing| existing.modified());
+    if thumbnail_modified.is_ok_and(|modified| modified >= source_modified) {
+        return Ok(());
+    }
+    let image = load_from_memory(&std::fs::read(source)?)?;
+    if let Some(parent) = thumbnail.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    DynamicImage::ImageRgb8(image.thumbnail(size, size).to_rgb8())
+        .save_with_format(thumbnail, ImageFormat::Jpeg)?;
+    Ok(())
+}
+
+pub fn index_page(state: &RenderState) -> String {
+    let mut body = String::new();
+    for (heading, prefix) in [
+        ("Photos and videos", "media/"),
+        ("Conversations", "threads/"),
+    ] {
+        _ = write!(body, "<h2>{heading}</h2><ul>");
+        let mut pages = state
+            .pages
+            .iter()
+            .filter(|(page, _)| page.starts_with(prefix))
+            .collect_vec();
+        if prefix == "threads/" {
+            pages.sort_by_key(|(_, rendered)| rendered.title.to_lowercase());
+        }
+        for (page, rendered) in pages {
+            _ = write!(
+                body,
+                "<li><a href=\"{}\">{}</a></li>",
+                // Page names come from escape_unsafe_name, so only `%` needs encoding
+                escape_html(&page.replace('%', "%25")),
+                escape_html(&rendered.title)
+            );
+        }
+        body.push_str("</ul>");
+    }
+    page_html("Archive", &body)
+}
+
+fn page_html(title: &str, body: &str) -> String {
+    let title = escape_html(title);
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title><style>{STYLE}</style></head>\
+<body><h1>{title}</h1>{body}</body></html>\n"
+    )
+}
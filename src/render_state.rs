@@ -0,0 +1,48 @@
+use crate::rendered_page::RenderedPage;
+use crate::sidecar::read_sidecar_json;
+use crate::sidecar::write_sidecar_json;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Fingerprints of the source files each page was last rendered from, kept inside the site so it can be rendered incrementally.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct RenderState {
+    /// Pages by their path relative to the site root
+    pub pages: BTreeMap<String, RenderedPage>,
+}
+impl RenderState {
+    pub fn path(site: &Path) -> PathBuf {
+        site.join(".render_state.json")
+    }
+    pub async fn load(site: &Path) -> eyre::Result<Self> {
+        read_sidecar_json(&Self::path(site)).await
+    }
+    pub async fn save(&self, site: &Path) -> eyre::Result<()> {
+        write_sidecar_json(&Self::path(site), self).await
+    }
+    /// Whether the page must be written again, either because its sources changed or because it is missing.
+    pub fn is_stale(&self, site: &Path, page: &str, fingerprint: u32) -> bool {
+        self.pages.get(page).map(|rendered| rendered.fingerprint) != Some(fingerprint)
+            || !site.join(page).exists()
+    }
+}
+
+/// Combines the path, size and modified time of every source of a page, plus anything else that changes its output.
+pub async fn source_fingerprint(sources: &[&Path], salt: &str) -> eyre::Result<u32> {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(salt.as_bytes());
+    for source in sources {
+        let metadata = tokio::fs::metadata(source).await?;
+        let modified = metadata
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        hasher.update(source.to_string_lossy().as_bytes());
+        hasher.update(&metadata.len().to_le_bytes());
+        hasher.update(&modified.as_nanos().to_le_bytes());
+    }
+    Ok(hasher.finalize())
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A page written by the render command, remembered so unchanged pages can be skipped and listed on the index.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RenderedPage {
+    pub fingerprint: u32,
+    pub title: String,
+}
@@ -0,0 +1,88 @@
+use crate::entry_coverage::EntryCoverage;
+use crate::entry_coverage_record::EntryCoverageRecord;
+use crate::sidecar::sidecar_dir;
+use crate::zip_fingerprint::ZipFingerprint;
+use chrono::DateTime;
+use chrono::Local;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Proof that every file entry of a source zip is present in the destination, written before the zip is retired.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RetireReport {
+    pub zip: PathBuf,
+    /// The zip file as it was when it was checked
+    pub fingerprint: ZipFingerprint,
+    pub destination: PathBuf,
+    /// Perceptual hash distance that counted as equivalent
+    pub similarity: u32,
+    pub checked_at: DateTime<Local>,
+    pub entries: Vec<EntryCoverageRecord>,
+    /// Entries, or the whole zip, that could not be read
+    pub unreadable: Vec<String>,
+    /// Whether the entries were recovered from local file headers because the central directory could not be read,
+    /// in which case there is no complete list of entries to check against
+    #[serde(default)]
+    pub recovered: bool,
+    /// Set only when every entry is covered, nothing was unreadable and the zip was read normally
+    pub safe_to_delete: bool,
+}
+impl RetireReport {
+    pub fn new(
+        zip: PathBuf,
+        fingerprint: ZipFingerprint,
+        destination: PathBuf,
+        similarity: u32,
+        entries: Vec<EntryCoverageRecord>,
+        unreadable: Vec<String>,
+        recovered: bool,
+    ) -> Self {
+        let safe_to_delete = !recovered
+            && unreadable.is_empty()
+            && entries.iter().all(|entry| entry.coverage.is_covered());
+        Self {
+            zip,
+            fingerprint,
+            destination,
+            similarity,
+            checked_at: Local::now(),
+            entries,
+            unreadable,
+            recovered,
+            safe_to_delete,
+        }
+    }
+
+    /// Reports are kept in the destination sidecar directory, named after the zip and the time of the check.
+    pub fn path(&self) -> PathBuf {
+        let zip_name = self
+            .zip
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown_zip".to_string());
+        retire_dir(&self.destination).join(format!(
+            "{zip_name}.{}.json",
+            self.checked_at.format("%Y%m%d-%H%M%S")
+        ))
+    }
+
+    /// Number of entries that are byte-identical, perceptually equivalent, mismatched and missing.
+    pub fn counts(&self) -> (usize, usize, usize, usize) {
+        let mut counts = (0, 0, 0, 0);
+        for entry in &self.entries {
+            match entry.coverage {
+                EntryCoverage::ByteIdentical { .. } => counts.0 += 1,
+                EntryCoverage::Perceptual { .. } => counts.1 += 1,
+                EntryCoverage::Mismatch { .. } => counts.2 += 1,
+                EntryCoverage::Missing => counts.3 += 1,
+            }
+        }
+        counts
+    }
+}
+
+pub fn retire_dir(destination: &Path) -> PathBuf {
+    sidecar_dir(destination).join("retire")
+}
@@ -0,0 +1,18 @@
+use crate::zip_contribution::ZipContribution;
+use serde::Serialize;
+
+/// Space used by keeping every source zip compared to the merged destination.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SavingsReport {
+    /// Size of all source zip files on disk
+    pub total_zip_bytes: u64,
+    /// Uncompressed bytes of every file entry in every zip
+    pub total_entry_bytes: u64,
+    /// Uncompressed bytes of each distinct (name, CRC32) content
+    pub unique_content_bytes: u64,
+    /// Uncompressed bytes of image variants that are perceptually equivalent to a smaller variant that is kept instead
+    pub perceptual_dedup_bytes: u64,
+    /// Uncompressed bytes the merged destination needs
+    pub destination_bytes: u64,
+    pub zips: Vec<ZipContribution>,
+}
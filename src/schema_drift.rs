@@ -0,0 +1,13 @@
+use crate::meta::schema_change::SchemaChange;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// How the format of one kind of JSON file changed from one export to the next export that has it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDrift {
+    /// Path pattern of the files, such as `messages/inbox/*/message_*.json`
+    pub pattern: String,
+    pub old_export: NaiveDate,
+    pub new_export: NaiveDate,
+    pub changes: Vec<SchemaChange>,
+}
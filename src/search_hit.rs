@@ -0,0 +1,14 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A search result, pointing back at the file and every zip it was found in.
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchHit {
+    pub kind: String,
+    pub thread: Option<String>,
+    pub sender: Option<String>,
+    pub timestamp_ms: Option<i64>,
+    pub text: String,
+    pub path_inside_zip: PathBuf,
+    pub zips: Vec<PathBuf>,
+}
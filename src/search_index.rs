@@ -0,0 +1,230 @@
+use crate::meta::text_record::TextRecord;
+use crate::search_hit::SearchHit;
+use crate::search_query::SearchQuery;
+use crate::sidecar::sidecar_dir;
+use duckdb::Connection;
+use duckdb::params;
+use duckdb::params_from_iter;
+use duckdb::types::Value;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
+
+/// Text from Meta JSON files across every source zip, stored in DuckDB in the destination sidecar directory.
+///
+/// Queries use DuckDB full-text search when the `fts` extension can be loaded, and fall back to `ILIKE` otherwise.
+pub struct SearchIndex {
+    conn: Connection,
+    fts: bool,
+}
+impl SearchIndex {
+    pub fn path(destination: &Path) -> PathBuf {
+        sidecar_dir(destination).join("search.duckdb")
+    }
+
+    pub fn open(path: &Path) -> eyre::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE SEQUENCE IF NOT EXISTS texts_id;
+            CREATE TABLE IF NOT EXISTS texts(
+                id BIGINT DEFAULT nextval('texts_id'),
+                kind TEXT,
+                thread TEXT,
+                sender TEXT,
+                ts_ms BIGINT,
+                text TEXT,
+                path_inside_zip TEXT,
+                zip TEXT
+            );
+            CREATE TABLE IF NOT EXISTS indexed_zips(zip TEXT PRIMARY KEY, bytes BIGINT);",
+        )?;
+        let fts = match conn.execute_batch("INSTALL fts; LOAD fts;") {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Full-text search is unavailable ({e}), falling back to substring matching");
+                false
+            }
+        };
+        Ok(Self { conn, fts })
+    }
+
+    /// Zips already in the index, with the size they had when they were indexed.
+    pub fn indexed_zips(&self) -> eyre::Result<HashSet<(String, i64)>> {
+        let mut stmt = self.conn.prepare("SELECT zip, bytes FROM indexed_zips")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Replaces everything indexed for a zip with the given records.
+    ///
+    /// A zip that was not `complete`ly read is searchable but not listed as indexed, so the next search reads it again.
+    pub fn index_zip(
+        &mut self,
+        zip: &str,
+        bytes: i64,
+        records: &[(PathBuf, TextRecord)],
+        complete: bool,
+    ) -> eyre::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM texts WHERE zip = ?", params![zip])?;
+        tx.execute("DELETE FROM indexed_zips WHERE zip = ?", params![zip])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO texts(kind, thread, sender, ts_ms, text, path_inside_zip, zip) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            for (path_inside_zip, record) in records {
+                stmt.execute(params![
+                    record.kind.as_str(),
+                    record.thread,
+                    record.sender,
+                    record.timestamp_ms,
+                    record.text,
+                    path_inside_zip.to_string_lossy(),
+                    zip,
+                ])?;
+            }
+        }
+        if complete {
+            tx.execute(
+                "INSERT INTO indexed_zips(zip, bytes) VALUES (?, ?)",
+                params![zip, bytes],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Drops zips that are no longer in the profile sources. Returns whether anything was removed.
+    ///
+    /// Stale zips are found by their records, since a zip that was not completely read is never listed as indexed.
+    pub fn remove_zips_except(&mut self, zips: &HashSet<String>) -> eyre::Result<bool> {
+        let stale = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT zip FROM texts UNION SELECT zip FROM indexed_zips")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.filter(|zip| zip.as_ref().map_or(true, |zip| !zips.contains(zip)))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let tx = self.conn.transaction()?;
+        for zip in &stale {
+            info!("Removing {zip} from the search index");
+            tx.execute("DELETE FROM texts WHERE zip = ?", params![zip])?;
+            tx.execute("DELETE FROM indexed_zips WHERE zip = ?", params![zip])?;
+        }
+        tx.commit()?;
+        Ok(!stale.is_empty())
+    }
+
+    /// Whether full-text search is available but its index has not been built yet.
+    pub fn fts_index_missing(&self) -> eyre::Result<bool> {
+        if !self.fts {
+            return Ok(false);
+        }
+        let schemas: i64 = self.conn.query_row(
+            "SELECT count(*) FROM duckdb_schemas() WHERE schema_name = 'fts_main_texts'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(schemas == 0)
+    }
+
+    /// Rebuilds the full-text index after records were added.
+    pub fn rebuild_fts(&self) -> eyre::Result<()> {
+        if self.fts {
+            info!("Rebuilding full-text index");
+            self.conn.execute_batch(
+                "PRAGMA create_fts_index('texts', 'id', 'text', 'thread', 'sender', overwrite=1);",
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn search(&self, query: &SearchQuery) -> eyre::Result<Vec<SearchHit>> {
+        let mut args: Vec<Value> = Vec::new();
+        let score = if self.fts {
+            args.push(Value::Text(query.text.clone()));
+            "fts_main_texts.match_bm25(id, ?)"
+        } else {
+            args.push(Value::Text(contains_pattern(&query.text)));
+            "CASE WHEN text ILIKE ? ESCAPE '\\' THEN 1.0 END"
+        };
+        let mut filters = vec!["score IS NOT NULL".to_string()];
+        if let Some(kind) = query.kind {
+            filters.push("kind = ?".to_string());
+            args.push(Value::Text(kind.as_str().to_string()));
+        }
+        if let Some(sender) = &query.sender {
+            filters.push("sender ILIKE ? ESCAPE '\\'".to_string());
+            args.push(Value::Text(contains_pattern(sender)));
+        }
+        if let Some(thread) = &query.thread {
+            filters.push("thread ILIKE ? ESCAPE '\\'".to_string());
+            args.push(Value::Text(contains_pattern(thread)));
+        }
+        if let Some(since_ms) = query.since_ms {
+            filters.push("ts_ms >= ?".to_string());
+            args.push(Value::BigInt(since_ms));
+        }
+        if let Some(until_ms) = query.until_ms {
+            filters.push("ts_ms < ?".to_string());
+            args.push(Value::BigInt(until_ms));
+        }
+        args.push(Value::BigInt(query.limit as i64));
+
+        // The same text is usually in every export, so results are grouped and list every zip they were found in.
+        let sql = format!(
+            "SELECT kind, thread, sender, ts_ms, text, path_inside_zip, string_agg(DISTINCT zip, '\n') AS zips, max(score) AS best
+            FROM (SELECT *, {score} AS score FROM texts)
+            WHERE {}
+            GROUP BY kind, thread, sender, ts_ms, text, path_inside_zip
+            ORDER BY best DESC, ts_ms
+            LIMIT ?",
+            filters.join(" AND ")
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(args), |row| {
+            let zips: String = row.get(6)?;
+            Ok(SearchHit {
+                kind: row.get(0)?,
+                thread: row.get(1)?,
+                sender: row.get(2)?,
+                timestamp_ms: row.get(3)?,
+                text: row.get(4)?,
+                path_inside_zip: PathBuf::from(row.get::<_, String>(5)?),
+                zips: zips.lines().map(PathBuf::from).collect(),
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+}
+
+/// Builds an `ILIKE` pattern matching the text anywhere, with its own wildcards escaped.
+fn contains_pattern(text: &str) -> String {
+    let mut rtn = String::with_capacity(text.len() + 2);
+    rtn.push('%');
+    for c in text.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            rtn.push('\\');
+        }
+        rtn.push(c);
+    }
+    rtn.push('%');
+    rtn
+}
+
+#[cfg(test)]
+mod test {
+    use super::contains_pattern;
+
+    #[test]
+    fn escapes_like_wildcards() {
+        assert_eq!(contains_pattern("hello"), "%hello%");
+        assert_eq!(contains_pattern("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+}
@@ -0,0 +1,17 @@
+use crate::meta::text_kind::TextKind;
+
+/// Text to look for, narrowed by optional filters.
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub kind: Option<TextKind>,
+    /// Case-insensitive substring of the sender name
+    pub sender: Option<String>,
+    /// Case-insensitive substring of the thread title
+    pub thread: Option<String>,
+    /// Inclusive lower bound, in milliseconds since the epoch
+    pub since_ms: Option<i64>,
+    /// Exclusive upper bound, in milliseconds since the epoch
+    pub until_ms: Option<i64>,
+    pub limit: usize,
+}
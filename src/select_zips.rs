@@ -0,0 +1,51 @@
+use crate::get_zips;
+use crate::path_to_zip::PathToZip;
+use cloud_terrastodon_user_input::Choice;
+use cloud_terrastodon_user_input::FzfArgs;
+use eyre::bail;
+use itertools::Itertools;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Returns the zips named on the command line, or prompts to pick from the zips in the profile sources when none were given.
+pub async fn select_zips(
+    paths: Vec<PathBuf>,
+    sources: &[PathBuf],
+    non_interactive: bool,
+    header: &str,
+) -> eyre::Result<Vec<PathToZip>> {
+    if !paths.is_empty() {
+        let mut zips = Vec::with_capacity(paths.len());
+        for path in paths {
+            if !path.is_file() {
+                bail!("Zip {} does not exist", path.display());
+            }
+            zips.push(PathToZip::new(Arc::new(path)));
+        }
+        return Ok(zips);
+    }
+    if non_interactive {
+        bail!("No zips given and running in non-interactive mode");
+    }
+    let (zips, _) = get_zips::get_zips(sources).await?;
+    let choices = zips
+        .into_iter()
+        .sorted_by_key(|zip| zip.to_path_buf())
+        .map(|zip| Choice {
+            key: zip.display().to_string(),
+            value: zip,
+        })
+        .collect_vec();
+    let selected = cloud_terrastodon_user_input::pick_many(FzfArgs {
+        choices,
+        header: Some(header.to_string()),
+        ..Default::default()
+    })?
+    .into_iter()
+    .map(|choice| choice.value)
+    .collect_vec();
+    if selected.is_empty() {
+        bail!("No zips selected");
+    }
+    Ok(selected)
+}
@@ -0,0 +1,53 @@
+use eyre::Context;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Name of the directory inside the destination where thrumzip keeps its own bookkeeping files.
+/// It is not part of any zip, so [`crate::gather_existing_files::gather_existing_files`] skips it.
+pub const SIDECAR_DIR_NAME: &str = ".thrumzip";
+
+pub fn sidecar_dir(destination: &Path) -> PathBuf {
+    destination.join(SIDECAR_DIR_NAME)
+}
+
+/// Where the untouched bytes of JSON files are kept when the destination copy was transcoded.
+/// The layout mirrors the destination, so `dest/a/b.json` is kept at `dest/.thrumzip/original_json/a/b.json`.
+pub fn original_json_path(destination: &Path, path_on_disk: &Path) -> Option<PathBuf> {
+    let relative = path_on_disk.strip_prefix(destination).ok()?;
+    Some(
+        sidecar_dir(destination)
+            .join("original_json")
+            .join(relative),
+    )
+}
+
+/// The file holding the bytes that were extracted from the zip: the kept original for transcoded JSON, otherwise the file itself.
+pub fn content_path(destination: &Path, path_on_disk: &Path) -> PathBuf {
+    original_json_path(destination, path_on_disk)
+        .filter(|original| original.exists())
+        .unwrap_or_else(|| path_on_disk.to_path_buf())
+}
+
+/// Reads a JSON sidecar file, returning the default value if it does not exist yet.
+pub async fn read_sidecar_json<T: DeserializeOwned + Default>(path: &Path) -> eyre::Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let data = tokio::fs::read(path)
+        .await
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_slice(&data).wrap_err_with(|| format!("Failed to parse {}", path.display()))
+}
+
+pub async fn write_sidecar_json<T: Serialize>(path: &Path, value: &T) -> eyre::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let data = serde_json::to_vec_pretty(value)?;
+    tokio::fs::write(path, data)
+        .await
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
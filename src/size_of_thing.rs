@@ -79,6 +79,11 @@ impl KnownSize for &str {
         std::mem::size_of::<Self>() + self.len()
     }
 }
+impl KnownSize for String {
+    fn size_in_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.len()
+    }
+}
 impl KnownSize for Entry {
     fn size_in_bytes(&self) -> usize {
         std::mem::size_of::<Self>()
@@ -96,6 +101,15 @@ where
     }
 }
 
+impl<T> KnownSize for Option<T>
+where
+    T: KnownSize,
+{
+    fn size_in_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.as_ref().map_or(0, |item| item.size_in_bytes())
+    }
+}
+
 impl<L, R> KnownSize for (L, R)
 where
     L: KnownSize,
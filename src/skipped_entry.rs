@@ -0,0 +1,44 @@
+use crate::path_to_zip::PathToZip;
+use tracing::info;
+use tracing::warn;
+
+/// A zip, or an entry inside a zip, that could not be read and was left out of the results.
+#[derive(Debug, Clone)]
+pub struct SkippedEntry {
+    pub path_to_zip: PathToZip,
+    /// The raw entry name, or `None` when the whole zip was skipped.
+    pub entry_name: Option<String>,
+    pub reason: String,
+}
+
+impl std::fmt::Display for SkippedEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.entry_name {
+            Some(entry_name) => write!(
+                f,
+                "{:?} in {}: {}",
+                entry_name,
+                self.path_to_zip.display(),
+                self.reason
+            ),
+            None => write!(f, "{}: {}", self.path_to_zip.display(), self.reason),
+        }
+    }
+}
+
+/// Logs a final report of everything that was skipped while reading zips.
+pub fn report_skipped(skipped: &[SkippedEntry]) {
+    if skipped.is_empty() {
+        info!("No zips or entries were skipped.");
+        return;
+    }
+    let zips = skipped.iter().filter(|s| s.entry_name.is_none()).count();
+    warn!(
+        "Skipped {} zips and {} entries that could not be read:",
+        zips,
+        skipped.len() - zips
+    );
+    for skipped in skipped {
+        warn!("  {skipped}");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::name_sanitizer_kind::NameSanitizerKind;
 use async_trait::async_trait;
 use eye_config::persistable_state::PersistableState;
 use eye_config::persistence_key::PersistenceKey;
@@ -40,6 +41,15 @@ pub struct Profile {
     pub similarity: u32,
     /// Name of the profile
     pub name: String,
+    /// Repair Meta's mojibake text encoding in JSON files while extracting
+    #[serde(default)]
+    pub fix_json_encoding: bool,
+    /// Warn about entry names that only differ by case, which collide on Windows and macOS destinations
+    #[serde(default)]
+    pub detect_case_collisions: bool,
+    /// How entry names are made safe for the destination filesystem
+    #[serde(default)]
+    pub name_sanitizer: NameSanitizerKind,
 }
 impl Profile {
     pub fn new_example() -> Self {
@@ -48,6 +58,9 @@ impl Profile {
             name: "example".into(),
             sources: vec!["test_data/source".into()],
             similarity: DEFAULT_IMAGE_SIMILARITY_THRESHOLD,
+            fix_json_encoding: false,
+            detect_case_collisions: false,
+            name_sanitizer: NameSanitizerKind::None,
         }
     }
 }
@@ -0,0 +1,117 @@
+use crate::sidecar::read_sidecar_json;
+use crate::sidecar::sidecar_dir;
+use crate::sidecar::write_sidecar_json;
+use crate::zip_entry::ZipEntry;
+use itertools::Itertools;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Entries whose names cannot be sanitized (absolute paths, `..` components, odd encodings) are placed in this directory under an escaped name.
+pub const UNSAFE_NAMES_DIR: &str = "_unsafe_names";
+
+/// Escaped names longer than this are truncated and suffixed with a hash of the raw name.
+const MAX_ESCAPED_LEN: usize = 200;
+
+/// Returns the quarantine path for an entry name that failed sanitization.
+///
+/// Every byte outside `[A-Za-z0-9._-]` is percent-encoded, so the result is a single path component and cannot escape the quarantine directory.
+pub fn quarantine_path(raw_name: &str) -> PathBuf {
+    Path::new(UNSAFE_NAMES_DIR).join(escape_unsafe_name(raw_name))
+}
+
+pub fn escape_unsafe_name(raw_name: &str) -> String {
+    let mut escaped = String::with_capacity(raw_name.len());
+    for (i, byte) in raw_name.bytes().enumerate() {
+        let keep = byte.is_ascii_alphanumeric()
+            || matches!(byte, b'-' | b'_')
+            // A leading dot would make `.` and `..` names, so it is escaped.
+            || (byte == b'.' && i > 0);
+        if keep {
+            escaped.push(byte as char);
+        } else {
+            _ = write!(escaped, "%{byte:02X}");
+        }
+    }
+    if escaped.is_empty() {
+        escaped.push_str("%00");
+    }
+    if escaped.len() > MAX_ESCAPED_LEN {
+        escaped.truncate(MAX_ESCAPED_LEN);
+        _ = write!(escaped, "~{:08x}", crc32fast::hash(raw_name.as_bytes()));
+    }
+    escaped
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnsafeNameRecord {
+    /// Path relative to the destination where the entry was written.
+    pub quarantined: PathBuf,
+    /// The raw entry name from the zip central directory.
+    pub original: String,
+    /// The zip the entry was read from.
+    pub zip: PathBuf,
+}
+
+/// Mapping from quarantined paths back to the raw entry names, persisted in the destination sidecar directory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UnsafeNameMapping {
+    pub entries: Vec<UnsafeNameRecord>,
+}
+impl UnsafeNameMapping {
+    pub fn path(destination: &Path) -> PathBuf {
+        sidecar_dir(destination).join("unsafe_names.json")
+    }
+    pub async fn load(destination: &Path) -> eyre::Result<Self> {
+        read_sidecar_json(&Self::path(destination)).await
+    }
+    pub async fn save(&self, destination: &Path) -> eyre::Result<()> {
+        write_sidecar_json(&Self::path(destination), self).await
+    }
+    /// Adds records for every quarantined entry, ignoring ones already present.
+    pub fn extend<'a>(&mut self, entries: impl IntoIterator<Item = &'a ZipEntry>) {
+        let new_records = entries
+            .into_iter()
+            .filter(|entry| entry.is_quarantined())
+            .map(|entry| UnsafeNameRecord {
+                quarantined: entry.path_inside_zip.to_path_buf(),
+                original: entry.entry.name.clone(),
+                zip: entry.path_to_zip.to_path_buf(),
+            });
+        self.entries = self.entries.drain(..).chain(new_records).unique().collect();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::escape_unsafe_name;
+    use super::quarantine_path;
+    use std::path::Component;
+
+    #[test]
+    fn escapes_traversal() {
+        assert_eq!(escape_unsafe_name("../etc/passwd"), "%2E.%2Fetc%2Fpasswd");
+        assert_eq!(escape_unsafe_name("/abs.txt"), "%2Fabs.txt");
+        assert_eq!(escape_unsafe_name(".."), "%2E.");
+        assert_eq!(escape_unsafe_name(""), "%00");
+    }
+
+    #[test]
+    fn quarantine_path_is_single_component() {
+        for name in ["../../x", "C:\\Windows\\x", "/a/b", "ü/ß"] {
+            let path = quarantine_path(name);
+            assert_eq!(path.components().count(), 2);
+            assert!(path.components().all(|c| matches!(c, Component::Normal(_))));
+        }
+    }
+
+    #[test]
+    fn long_names_are_truncated() {
+        let name = "x".repeat(500);
+        let escaped = escape_unsafe_name(&name);
+        assert!(escaped.len() < 220);
+        assert_ne!(escaped, escape_unsafe_name(&"x".repeat(501)));
+    }
+}
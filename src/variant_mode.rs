@@ -0,0 +1,13 @@
+use clap::ValueEnum;
+
+/// Which variants of a selected entry the extract command writes.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VariantMode {
+    /// Every distinct variant, each in a folder named after its zip
+    All,
+    /// The variant sync would write without disambiguation, or the newest one when sync would keep several
+    #[default]
+    Canonical,
+    /// The variant from the zip given with `--zip`
+    FromZip,
+}
@@ -0,0 +1,161 @@
+use crate::name_sanitizer::NameSanitizer;
+use std::fmt::Write;
+
+/// Characters NTFS and exFAT refuse, plus `%` itself so the escaping stays reversible.
+const ESCAPED_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*', '%'];
+
+/// Device names Windows reserves regardless of extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Longest file name NTFS and exFAT accept, in UTF-16 code units.
+const MAX_COMPONENT_LEN: usize = 255;
+
+/// Extensions longer than this are not preserved when a name has to be shortened.
+const MAX_PRESERVED_EXTENSION_LEN: usize = 16;
+
+/// Percent-encodes names that are invalid on Windows filesystems.
+///
+/// Invalid characters, control characters, trailing dots and spaces, and the first character of reserved device names become `%XX`.
+/// Names that are still too long are shortened with a hash suffix; those cannot be reversed and rely on the recorded mapping.
+pub struct WindowsNameSanitizer;
+
+impl NameSanitizer for WindowsNameSanitizer {
+    fn sanitize_component(&self, component: &str) -> String {
+        let mut sanitized = String::with_capacity(component.len());
+        for c in component.chars() {
+            if ESCAPED_CHARS.contains(&c) || c.is_control() {
+                push_escaped(&mut sanitized, c);
+            } else {
+                sanitized.push(c);
+            }
+        }
+
+        // Windows silently strips trailing dots and spaces
+        let kept_len = sanitized.trim_end_matches(['.', ' ']).len();
+        let trailing = sanitized.split_off(kept_len);
+        for c in trailing.chars() {
+            push_escaped(&mut sanitized, c);
+        }
+
+        let stem = sanitized.split('.').next().unwrap_or_default().trim_end();
+        if RESERVED_NAMES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+        {
+            let first = sanitized.remove(0);
+            let mut escaped_first = String::new();
+            push_escaped(&mut escaped_first, first);
+            sanitized.insert_str(0, &escaped_first);
+        }
+
+        if sanitized.encode_utf16().count() > MAX_COMPONENT_LEN {
+            sanitized = shorten(&sanitized, component);
+        }
+        sanitized
+    }
+
+    fn unsanitize_component(&self, component: &str) -> String {
+        let bytes = component.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let escaped = (bytes[i] == b'%')
+                .then(|| component.get(i + 1..i + 3))
+                .flatten()
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            match escaped {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                None => {
+                    decoded.push(bytes[i]);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+}
+
+fn push_escaped(out: &mut String, c: char) {
+    let mut buf = [0u8; 4];
+    for byte in c.encode_utf8(&mut buf).bytes() {
+        _ = write!(out, "%{byte:02X}");
+    }
+}
+
+/// Truncates a name to fit, keeping a short extension and appending a hash of the original component.
+fn shorten(sanitized: &str, original: &str) -> String {
+    let extension = sanitized
+        .rsplit_once('.')
+        .map(|(_, extension)| extension)
+        .filter(|extension| extension.len() <= MAX_PRESERVED_EXTENSION_LEN)
+        .map(|extension| format!(".{extension}"))
+        .unwrap_or_default();
+    let suffix = format!("~{:08x}{extension}", crc32fast::hash(original.as_bytes()));
+    let budget = MAX_COMPONENT_LEN - suffix.encode_utf16().count();
+    let mut used = 0;
+    let mut shortened = String::new();
+    for c in sanitized.chars() {
+        used += c.len_utf16();
+        if used > budget {
+            break;
+        }
+        shortened.push(c);
+    }
+    shortened.push_str(&suffix);
+    shortened
+}
+
+#[cfg(test)]
+mod test {
+    use super::WindowsNameSanitizer;
+    use crate::name_sanitizer::NameSanitizer;
+
+    #[test]
+    fn escapes_invalid_names() {
+        let sanitizer = WindowsNameSanitizer;
+        assert_eq!(sanitizer.sanitize_component("a:b?.txt"), "a%3Ab%3F.txt");
+        assert_eq!(sanitizer.sanitize_component("50%"), "50%25");
+        assert_eq!(sanitizer.sanitize_component("name. "), "name%2E%20");
+        assert_eq!(sanitizer.sanitize_component("CON"), "%43ON");
+        assert_eq!(sanitizer.sanitize_component("con.txt"), "%63on.txt");
+        assert_eq!(sanitizer.sanitize_component("console.txt"), "console.txt");
+        assert_eq!(sanitizer.sanitize_component("photo.jpg"), "photo.jpg");
+    }
+
+    #[test]
+    fn escaping_is_reversible() {
+        let sanitizer = WindowsNameSanitizer;
+        for name in [
+            "a:b?.txt",
+            "50%",
+            "name. ",
+            "CON",
+            "tab\there",
+            "ü*ß",
+            "%41",
+        ] {
+            let sanitized = sanitizer.sanitize_component(name);
+            assert_eq!(sanitizer.unsanitize_component(&sanitized), name);
+        }
+    }
+
+    #[test]
+    fn long_names_are_shortened() {
+        let sanitizer = WindowsNameSanitizer;
+        let name = format!("{}.jpg", "a".repeat(300));
+        let sanitized = sanitizer.sanitize_component(&name);
+        assert!(sanitized.encode_utf16().count() <= 255);
+        assert!(sanitized.ends_with(".jpg"));
+        assert_ne!(
+            sanitized,
+            sanitizer.sanitize_component(&format!("{}.jpg", "a".repeat(301)))
+        );
+    }
+}
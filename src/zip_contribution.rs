@@ -0,0 +1,22 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// How much of one source zip is still needed to reproduce the merged destination.
+#[derive(Serialize, Debug, Clone)]
+pub struct ZipContribution {
+    pub zip: PathBuf,
+    /// Size of the zip file on disk
+    pub zip_bytes: u64,
+    /// Uncompressed bytes of file entries in the zip
+    pub entry_bytes: u64,
+    /// Uncompressed bytes whose content is not available from any other zip
+    pub needed_bytes: u64,
+    /// Number of entries whose content is not available from any other zip
+    pub needed_entries: usize,
+}
+impl ZipContribution {
+    /// Whether every entry of this zip can also be found in another zip.
+    pub fn contributes_nothing_new(&self) -> bool {
+        self.needed_entries == 0
+    }
+}
@@ -1,4 +1,7 @@
+use crate::get_splat_path::get_sanitized_splat_path;
 use crate::get_splat_path::get_splat_path;
+use crate::meta::mojibake::fix_mojibake_json;
+use crate::name_sanitizer::NameSanitizer;
 use crate::path_inside_zip::PathInsideZip;
 use crate::path_to_zip::PathToZip;
 use crate::size_of_thing::KnownCount;
@@ -14,6 +17,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
+use tracing::debug;
+use tracing::warn;
 
 #[derive(Clone)]
 pub struct ZipEntry {
@@ -39,6 +44,10 @@ impl ZipEntry {
     pub fn is_file(&self) -> bool {
         self.entry.kind() == EntryKind::File
     }
+    /// Whether the entry name failed sanitization and was placed under [`crate::unsafe_names::UNSAFE_NAMES_DIR`].
+    pub fn is_quarantined(&self) -> bool {
+        self.entry.sanitized_name().is_none()
+    }
     /// Reads the entire entry into a vector.
     pub async fn bytes(&self) -> tokio::io::Result<Vec<u8>> {
         let mut v = Vec::new();
@@ -53,21 +62,74 @@ impl ZipEntry {
             disambiguate,
         )
     }
-    pub async fn write_to_file(&self, dest: &Path) -> eyre::Result<()> {
-        let Some(parent) = dest.parent() else {
-            return Err(eyre::eyre!(
-                "Destination path {} has no parent directory.",
-                dest.display()
-            ));
+    pub fn get_sanitized_splat_path(
+        &self,
+        dest_dir: &Path,
+        disambiguate: bool,
+        sanitizer: &dyn NameSanitizer,
+    ) -> eyre::Result<PathBuf> {
+        get_sanitized_splat_path(
+            &self.path_inside_zip,
+            &self.path_to_zip,
+            dest_dir,
+            disambiguate,
+            sanitizer,
+        )
+    }
+    pub fn is_json(&self) -> bool {
+        self.path_inside_zip
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    }
+    /// Writes the entry with Meta's mojibake repaired, keeping the original bytes at `original_dest` so they can still be checked against the zip CRC.
+    /// Falls back to writing the entry unchanged when it does not parse or needs no repair.
+    pub async fn write_transcoded_json_to_file(
+        &self,
+        dest: &Path,
+        original_dest: &Path,
+    ) -> eyre::Result<()> {
+        let data = self.bytes().await?;
+        let fixed = match fix_mojibake_json(&data) {
+            Ok(fixed) => fixed,
+            Err(e) => {
+                warn!(
+                    "Failed to parse {} from {} as JSON, writing it unchanged: {e}",
+                    self.path_inside_zip.display(),
+                    self.path_to_zip.display()
+                );
+                None
+            }
         };
-        _ = tokio::fs::create_dir_all(parent).await;
+        let Some(fixed) = fixed else {
+            return write_bytes(dest, &data).await;
+        };
+        debug!(
+            "Repaired text encoding of {}, keeping original at {}",
+            dest.display(),
+            original_dest.display()
+        );
+        write_bytes(original_dest, &data).await?;
+        write_bytes(dest, &fixed).await
+    }
+    pub async fn write_to_file(&self, dest: &Path) -> eyre::Result<()> {
         let data = self.bytes().await?;
-        tokio::fs::write(&dest, data)
-            .await
-            .wrap_err_with(|| eyre::eyre!("Failed to write to {}", dest.display()))?;
-        Ok(())
+        write_bytes(dest, &data).await
     }
 }
+
+async fn write_bytes(dest: &Path, data: &[u8]) -> eyre::Result<()> {
+    let Some(parent) = dest.parent() else {
+        return Err(eyre::eyre!(
+            "Destination path {} has no parent directory.",
+            dest.display()
+        ));
+    };
+    _ = tokio::fs::create_dir_all(parent).await;
+    tokio::fs::write(&dest, data)
+        .await
+        .wrap_err_with(|| eyre::eyre!("Failed to write to {}", dest.display()))?;
+    Ok(())
+}
 impl KnownSize for ZipEntry {
     fn size_in_bytes(&self) -> usize {
         self.path_to_zip.size_in_bytes()
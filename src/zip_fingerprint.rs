@@ -0,0 +1,42 @@
+use eyre::Context;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+const READ_CHUNK_LEN: usize = 1024 * 1024;
+
+/// Identifies the exact contents of a zip file, so a check made against it is not applied to a different file at the same path.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZipFingerprint {
+    /// Size of the zip file on disk
+    pub bytes: u64,
+    /// CRC32 of the whole zip file
+    pub crc32: u32,
+}
+impl ZipFingerprint {
+    /// Reads the whole file to fingerprint it.
+    pub async fn of(path: &Path) -> eyre::Result<Self> {
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buf = vec![0u8; READ_CHUNK_LEN];
+        let mut bytes = 0;
+        loop {
+            let read = file
+                .read(&mut buf)
+                .await
+                .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+            bytes += read as u64;
+        }
+        Ok(Self {
+            bytes,
+            crc32: hasher.finalize(),
+        })
+    }
+}